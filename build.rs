@@ -0,0 +1,16 @@
+use std::fs;
+
+// Makes the locked roon-api revision available to --diagnostics
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let revision = lock.split("[[package]]")
+        .find(|package| package.contains("name = \"roon-api\""))
+        .and_then(|package| package.lines().find_map(|line| line.strip_prefix("source = ")))
+        .and_then(|source| source.trim_matches('"').rsplit_once('#'))
+        .map(|(_, revision)| revision.to_owned())
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    println!("cargo:rustc-env=ROON_API_REVISION={}", revision);
+}
@@ -0,0 +1,318 @@
+use crossterm::{cursor, queue, terminal};
+use image::{imageops::FilterType, DynamicImage, RgbImage};
+use ratatui::{layout::Rect, style::{Color, Style}, text::{Line, Span}};
+use std::{env, io::{self, Write}};
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const KITTY_CHUNK_SIZE: usize = 4096;
+const DEFAULT_CELL_WIDTH: u16 = 8;
+const DEFAULT_CELL_HEIGHT: u16 = 16;
+const UPPER_HALF_BLOCK: &str = "▀";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    Blocks,
+}
+
+impl Protocol {
+    pub fn detect() -> Self {
+        // Querying the terminal would interfere with the event reader, use the environment instead
+        let term = env::var("TERM").unwrap_or_default();
+        let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+        if env::var("KITTY_WINDOW_ID").is_ok()
+            || term.contains("kitty")
+            || term_program == "WezTerm"
+            || term_program == "ghostty" {
+            Protocol::Kitty
+        } else if term.starts_with("foot") || term.starts_with("mlterm") || term.starts_with("contour") {
+            Protocol::Sixel
+        } else {
+            Protocol::Blocks
+        }
+    }
+}
+
+pub struct AlbumArt {
+    protocol: Protocol,
+    image_key: Option<String>,
+    image: Option<DynamicImage>,
+    blocks: Option<(u16, u16, Vec<Line<'static>>)>,
+    drawn_area: Option<Rect>,
+    dirty: bool,
+}
+
+impl AlbumArt {
+    pub fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            image_key: None,
+            image: None,
+            blocks: None,
+            drawn_area: None,
+            dirty: false,
+        }
+    }
+
+    pub fn is_blocks(&self) -> bool {
+        self.protocol == Protocol::Blocks
+    }
+
+    pub fn has_image(&self) -> bool {
+        self.image.is_some()
+    }
+
+    pub fn set_image_key(&mut self, image_key: Option<&str>) {
+        if self.image_key.as_deref() != image_key {
+            // Remove the art of the previous track until the new image data arrives
+            self.image_key = image_key.map(|image_key| image_key.to_owned());
+            self.image = None;
+            self.blocks = None;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_image_data(&mut self, data: &[u8]) {
+        self.image = match image::load_from_memory(data) {
+            Ok(image) => Some(image),
+            Err(err) => {
+                log::warn!("Failed to decode album art: {}", err);
+
+                None
+            }
+        };
+        self.blocks = None;
+        self.dirty = true;
+    }
+
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn get_block_lines(&mut self, width: u16, height: u16, no_unicode_symbols: bool) -> Vec<Line<'static>> {
+        let image = match self.image.as_ref() {
+            Some(image) => image,
+            None => return Vec::new(),
+        };
+
+        match self.blocks.as_ref() {
+            Some((cached_width, cached_height, lines)) if *cached_width == width && *cached_height == height => {
+                return lines.clone();
+            }
+            _ => (),
+        }
+
+        // Without Unicode symbols only the background of a cell can be used, giving one pixel per cell
+        let pixel_rows = if no_unicode_symbols {height} else {height * 2};
+        let pixels = image.resize_exact(width as u32, pixel_rows as u32, FilterType::Triangle).to_rgb8();
+        let lines = (0..height as u32)
+            .map(|row| {
+                let spans = (0..width as u32)
+                    .map(|column| {
+                        if no_unicode_symbols {
+                            Span::styled(" ", Style::default().bg(get_color(&pixels, column, row)))
+                        } else {
+                            let style = Style::default()
+                                .fg(get_color(&pixels, column, row * 2))
+                                .bg(get_color(&pixels, column, row * 2 + 1));
+
+                            Span::styled(UPPER_HALF_BLOCK, style)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>();
+
+        self.blocks = Some((width, height, lines.clone()));
+
+        lines
+    }
+
+    // Returns true when the screen has to be cleared and redrawn to remove the image
+    pub fn draw_graphics<W: Write>(&mut self, writer: &mut W, area: Option<Rect>) -> io::Result<bool> {
+        if self.protocol == Protocol::Blocks {
+            return Ok(false);
+        }
+
+        let area = if self.image.is_some() {area} else {None};
+
+        if area == self.drawn_area && !self.dirty {
+            return Ok(false);
+        }
+
+        if self.drawn_area.take().is_some() {
+            match self.protocol {
+                Protocol::Kitty => {
+                    write!(writer, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+                }
+                _ => {
+                    // Sixel images are part of the screen content
+                    self.dirty = true;
+
+                    return Ok(true);
+                }
+            }
+        }
+
+        self.dirty = false;
+
+        if let (Some(area), Some(image)) = (area, self.image.as_ref()) {
+            let graphics = match self.protocol {
+                Protocol::Kitty => encode_kitty(&image.to_rgb8(), area),
+                _ => {
+                    let (cell_width, cell_height) = get_cell_size();
+                    let width = (area.width * cell_width) as u32;
+                    let height = (area.height * cell_height) as u32;
+
+                    encode_sixel(&image.resize_exact(width, height, FilterType::Triangle).to_rgb8())
+                }
+            };
+
+            queue!(writer, cursor::SavePosition, cursor::MoveTo(area.x, area.y))?;
+            write!(writer, "{}", graphics)?;
+            queue!(writer, cursor::RestorePosition)?;
+
+            self.drawn_area = Some(area);
+        }
+
+        writer.flush()?;
+
+        Ok(false)
+    }
+}
+
+fn get_color(pixels: &RgbImage, x: u32, y: u32) -> Color {
+    let [r, g, b] = pixels.get_pixel(x, y).0;
+
+    Color::Rgb(r, g, b)
+}
+
+fn get_cell_size() -> (u16, u16) {
+    match terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => (DEFAULT_CELL_WIDTH, DEFAULT_CELL_HEIGHT),
+    }
+}
+
+fn encode_kitty(pixels: &RgbImage, area: Rect) -> String {
+    let (width, height) = pixels.dimensions();
+    let data = encode_base64(pixels.as_raw());
+    let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+    let mut kitty = String::new();
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = if index + 1 < chunks.len() {1} else {0};
+        // Base64 output only contains ASCII characters
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+
+        if index == 0 {
+            // Transmit raw RGB data and scale it into the cell area, without moving the cursor
+            kitty.push_str(&format!(
+                "\x1b_Ga=T,f=24,s={},v={},c={},r={},C=1,q=2,m={};{}\x1b\\",
+                width, height, area.width, area.height, more, chunk
+            ));
+        } else {
+            kitty.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+
+    kitty
+}
+
+fn encode_sixel(pixels: &RgbImage) -> String {
+    let (width, height) = pixels.dimensions();
+    let (width, height) = (width as usize, height as usize);
+    let mut sixel = format!("\x1bPq\"1;1;{};{}", width, height);
+
+    // Use a 6x6x6 color cube as palette, the color levels are specified as percentages
+    for index in 0..216 {
+        sixel.push_str(&format!("#{};2;{};{};{}", index, index / 36 * 20, index / 6 % 6 * 20, index % 6 * 20));
+    }
+
+    let indices = pixels.as_raw()
+        .chunks(3)
+        .map(|rgb| {
+            let level = |value: u8| (value as usize * 5 + 127) / 255;
+
+            level(rgb[0]) * 36 + level(rgb[1]) * 6 + level(rgb[2])
+        })
+        .collect::<Vec<_>>();
+
+    for band in (0..height).step_by(6) {
+        let band_height = (height - band).min(6);
+        let mut used = [false; 216];
+
+        for y in band..band + band_height {
+            for x in 0..width {
+                used[indices[y * width + x]] = true;
+            }
+        }
+
+        for color in (0..216).filter(|color| used[*color]) {
+            let mut run: Option<(char, usize)> = None;
+
+            sixel.push_str(&format!("#{}", color));
+
+            for x in 0..width {
+                let bits = (0..band_height)
+                    .filter(|dy| indices[(band + dy) * width + x] == color)
+                    .fold(0, |bits, dy| bits | 1 << dy);
+                let sixel_char = char::from(63 + bits as u8);
+
+                run = match run {
+                    Some((run_char, count)) if run_char == sixel_char => Some((run_char, count + 1)),
+                    Some((run_char, count)) => {
+                        push_sixel_run(&mut sixel, run_char, count);
+                        Some((sixel_char, 1))
+                    }
+                    None => Some((sixel_char, 1)),
+                };
+            }
+
+            if let Some((run_char, count)) = run {
+                push_sixel_run(&mut sixel, run_char, count);
+            }
+
+            // Return to the start of the band for the next color
+            sixel.push('$');
+        }
+
+        sixel.push('-');
+    }
+
+    sixel.push_str("\x1b\\");
+    sixel
+}
+
+fn push_sixel_run(sixel: &mut String, sixel_char: char, count: usize) {
+    if count > 3 {
+        sixel.push_str(&format!("!{}{}", count, sixel_char));
+    } else {
+        (0..count).for_each(|_| sixel.push(sixel_char));
+    }
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    let mut base64 = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize;
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                base64.push(BASE64_CHARS[triple >> (18 - index * 6) & 0x3f] as char);
+            } else {
+                base64.push('=');
+            }
+        }
+    }
+
+    base64
+}
@@ -0,0 +1,36 @@
+// The clipboard is kept open once used, on Linux the copied text is served by it
+#[derive(Default)]
+pub struct Clipboard {
+    #[cfg(feature = "clipboard")]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    #[cfg(feature = "clipboard")]
+    pub fn set_text(&mut self, text: String) -> bool {
+        if self.inner.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.inner = Some(clipboard),
+                Err(err) => {
+                    log::warn!("Clipboard not available: {}", err);
+                    return false;
+                }
+            }
+        }
+
+        match self.inner.as_mut().map(|clipboard| clipboard.set_text(text)) {
+            Some(Ok(())) => true,
+            Some(Err(err)) => {
+                log::warn!("Failed to copy to the clipboard: {}", err);
+                false
+            }
+            None => false,
+        }
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    pub fn set_text(&mut self, _text: String) -> bool {
+        log::warn!("Clipboard not available: built without the clipboard feature");
+        false
+    }
+}
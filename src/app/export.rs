@@ -0,0 +1,32 @@
+use roon_api::transport::QueueItem;
+use std::{fs, io, path::Path};
+
+// Writes an "Artist - Title" line per queue item, an .m3u file gets extended M3U info lines
+pub fn write_queue(path: &Path, items: &[QueueItem]) -> io::Result<()> {
+    let is_m3u = path.extension()
+        .map(|extension| extension.eq_ignore_ascii_case("m3u") || extension.eq_ignore_ascii_case("m3u8"))
+        .unwrap_or_default();
+    let mut content = if is_m3u {"#EXTM3U\n".to_owned()} else {String::new()};
+
+    for item in items {
+        let two_line = &item.two_line;
+        let line = if two_line.line2.is_empty() {
+            two_line.line1.to_owned()
+        } else {
+            format!("{} - {}", two_line.line2, two_line.line1)
+        };
+
+        if is_m3u {
+            content.push_str(&format!("#EXTINF:{},{}\n", item.length, line));
+        } else {
+            content.push_str(&line);
+            content.push('\n');
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, content)
+}
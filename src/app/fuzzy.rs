@@ -0,0 +1,163 @@
+use any_ascii::any_ascii;
+
+const MATCH_SCORE: i32 = 16;
+const LEADING_BONUS: i32 = 32;
+const WORD_BONUS: i32 = 24;
+const CONSECUTIVE_BONUS: i32 = 16;
+const GAP_PENALTY: i32 = 1;
+
+// Scores the characters of the pattern as an ordered subsequence of the title,
+// favoring matches at the start of the title or its words and consecutive matches.
+// With split set, all words count as start of the title, e.g. for artist names
+pub fn get_score(title: &str, pattern: &str, split: bool) -> Option<i32> {
+    let title = any_ascii(title).to_ascii_lowercase();
+    let title = if split {
+        title.as_str()
+    } else {
+        title.strip_prefix("the ").unwrap_or(&title)
+    };
+    let title = title.as_bytes();
+    let pattern = any_ascii(pattern).to_ascii_lowercase();
+    let mut prev_row: Option<Vec<Option<i32>>> = None;
+
+    for pattern_char in pattern.bytes() {
+        let mut row = vec![None; title.len()];
+        // Best score of the previous row, adjusted for the gap up to the current position
+        let mut best_gapped: Option<i32> = None;
+
+        for (index, title_char) in title.iter().enumerate() {
+            if *title_char == pattern_char {
+                let bonus = get_bonus(title, index, split);
+
+                row[index] = match prev_row.as_ref() {
+                    None if bonus == LEADING_BONUS => Some(MATCH_SCORE + bonus),
+                    None => Some(MATCH_SCORE + bonus - GAP_PENALTY * index as i32),
+                    Some(prev_row) => {
+                        let consecutive = index.checked_sub(1)
+                            .and_then(|prev| prev_row[prev])
+                            .map(|score| score + CONSECUTIVE_BONUS);
+                        let gapped = best_gapped.map(|score| score - GAP_PENALTY * index as i32);
+
+                        consecutive.max(gapped).map(|score| score + MATCH_SCORE + bonus)
+                    }
+                };
+            }
+
+            // Positions before the previous one leave a gap to the next position
+            if let Some(prev_row) = prev_row.as_ref() {
+                if let Some(score) = index.checked_sub(1).and_then(|prev| prev_row[prev]) {
+                    let score = score + GAP_PENALTY * index as i32;
+
+                    best_gapped = Some(best_gapped.map_or(score, |best| best.max(score)));
+                }
+            }
+        }
+
+        prev_row = Some(row);
+    }
+
+    match prev_row {
+        Some(row) => row.into_iter().flatten().max(),
+        None => Some(0),
+    }
+}
+
+// Returns the indices of all titles sharing the best score, in list order
+pub fn get_best_matches<'a, I>(titles: I, pattern: &str, split: bool) -> Vec<usize>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut best_score = None;
+    let mut matches = Vec::new();
+
+    for (index, title) in titles.enumerate() {
+        if let Some(score) = get_score(title, pattern, split) {
+            if best_score < Some(score) {
+                best_score = Some(score);
+                matches.clear();
+            }
+
+            if best_score == Some(score) {
+                matches.push(index);
+            }
+        }
+    }
+
+    matches
+}
+
+fn get_bonus(title: &[u8], index: usize, split: bool) -> i32 {
+    let is_word_start = index == 0
+        || (!title[index - 1].is_ascii_alphanumeric() && title[index].is_ascii_alphanumeric());
+
+    if index == 0 || (split && is_word_start) {
+        LEADING_BONUS
+    } else if is_word_start {
+        WORD_BONUS
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TITLES: [&str; 8] = [
+        "The Beatles",
+        "Beach House",
+        "Björk",
+        "Bob Dylan",
+        "Bon Iver",
+        "Miles Davis",
+        "John Coltrane",
+        "Ludwig van Beethoven",
+    ];
+
+    fn get_matches(pattern: &str, split: bool) -> Vec<usize> {
+        get_best_matches(TITLES.iter().copied(), pattern, split)
+    }
+
+    #[test]
+    fn the_prefix_is_ignored() {
+        assert_eq!(get_score("The Beatles", "beat", false), get_score("Beatles", "beat", false));
+        assert_eq!(get_matches("beat", false), [0]);
+        // Nothing is left to match in the prefix itself
+        assert_eq!(get_score("The Beatles", "th", false), None);
+        assert_eq!(get_matches("th", false), [7]);
+    }
+
+    #[test]
+    fn diacritics_are_folded() {
+        assert_eq!(get_matches("bjo", false), [2]);
+        assert_eq!(get_matches("bjö", false), [2]);
+        assert_eq!(get_score("Björk", "bjork", false), get_score("Bjork", "bjork", false));
+    }
+
+    #[test]
+    fn split_starts_every_word() {
+        // Without split the word closest to the start wins
+        assert_eq!(get_matches("d", false), [3]);
+        // Artists and Composers are jumped to by any of their names
+        assert_eq!(get_matches("d", true), [3, 5]);
+        assert_eq!(get_matches("bee", true), [7]);
+    }
+
+    #[test]
+    fn ties_are_returned_in_list_order() {
+        let matches = get_matches("b", false);
+
+        assert_eq!(matches, [0, 1, 2, 3, 4]);
+
+        // Repeating the key cycles through the ties, as the Browse jump does
+        let selected: Vec<usize> = (0..7).map(|cycle| matches[cycle % matches.len()]).collect();
+
+        assert_eq!(selected, [0, 1, 2, 3, 4, 0, 1]);
+    }
+
+    #[test]
+    fn no_match() {
+        assert_eq!(get_score("Bon Iver", "xyz", false), None);
+        assert!(get_matches("qq", false).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fs, path::{Path, PathBuf}};
+
+const MAX_ENTRIES: usize = 50;
+
+pub struct SearchHistory {
+    path: PathBuf,
+    entries: Vec<String>,
+    index: Option<usize>,
+}
+
+impl SearchHistory {
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Vec<String>>(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path: path.to_path_buf(),
+            entries,
+            index: None,
+        }
+    }
+
+    pub fn add(&mut self, entry: &str) {
+        let entry = entry.trim();
+
+        self.index = None;
+
+        if entry.is_empty() || self.entries.last().map(|last| last.as_str()) == Some(entry) {
+            return;
+        }
+
+        self.entries.push(entry.to_owned());
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+
+        self.save();
+    }
+
+    // Steps back to older entries, stays at the oldest one
+    pub fn prev(&mut self) -> Option<String> {
+        let index = match self.index {
+            Some(index) => index.saturating_sub(1),
+            None => self.entries.len().checked_sub(1)?,
+        };
+
+        self.index = Some(index);
+        self.entries.get(index).cloned()
+    }
+
+    // Steps forward to newer entries, passing the newest one results in empty input
+    pub fn next(&mut self) -> Option<String> {
+        let index = self.index? + 1;
+
+        if index < self.entries.len() {
+            self.index = Some(index);
+            self.entries.get(index).cloned()
+        } else {
+            self.index = None;
+            Some(String::new())
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.index = None;
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string_pretty(&self.entries)
+            .map_err(|err| err.to_string())
+            .and_then(|content| fs::write(&self.path, content).map_err(|err| err.to_string()));
+
+        if let Err(err) = result {
+            log::warn!("Failed to save {}: {}", self.path.display(), err);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    NextView,
+    PrevView,
+    ZoneSelect,
+    ZoneGroup,
+    ZonesOverview,
+    PlayPause,
+    PauseOnTrackEnd,
+    VolumeUp,
+    VolumeDown,
+    VolumeUpCoarse,
+    VolumeDownCoarse,
+    NextTrack,
+    PrevTrack,
+    QueueMode,
+    QueueAppend,
+    QueueClear,
+    QueueToggle,
+    CompactToggle,
+    Search,
+    Reconnect,
+    SplitLeft,
+    SplitRight,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 24] = [
+        Action::NextView,
+        Action::PrevView,
+        Action::ZoneSelect,
+        Action::ZoneGroup,
+        Action::ZonesOverview,
+        Action::PlayPause,
+        Action::PauseOnTrackEnd,
+        Action::VolumeUp,
+        Action::VolumeDown,
+        Action::VolumeUpCoarse,
+        Action::VolumeDownCoarse,
+        Action::NextTrack,
+        Action::PrevTrack,
+        Action::QueueMode,
+        Action::QueueAppend,
+        Action::QueueClear,
+        Action::QueueToggle,
+        Action::CompactToggle,
+        Action::Search,
+        Action::Reconnect,
+        Action::SplitLeft,
+        Action::SplitRight,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::NextView => "Next view",
+            Action::PrevView => "Previous view",
+            Action::ZoneSelect => "Select zone",
+            Action::ZoneGroup => "Group zones",
+            Action::ZonesOverview => "Zones overview",
+            Action::PlayPause => "Play/Pause",
+            Action::PauseOnTrackEnd => "Pause at end",
+            Action::VolumeUp => "Volume up",
+            Action::VolumeDown => "Volume down",
+            Action::VolumeUpCoarse => "Volume up (coarse)",
+            Action::VolumeDownCoarse => "Volume down (coarse)",
+            Action::NextTrack => "Next track",
+            Action::PrevTrack => "Previous track",
+            Action::QueueMode => "Queue mode",
+            Action::QueueAppend => "Append queue",
+            Action::QueueClear => "Clear queue",
+            Action::QueueToggle => "Show/hide queue",
+            Action::CompactToggle => "Compact lists",
+            Action::Search => "Search",
+            Action::Reconnect => "Reconnect now",
+            Action::SplitLeft => "Narrow Browse",
+            Action::SplitRight => "Widen Browse",
+            Action::Help => "This help page",
+            Action::Quit => "Quit",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+pub struct Keymap {
+    bindings: HashMap<(KeyCode, KeyModifiers), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let defaults = [
+            (KeyCode::Tab, KeyModifiers::NONE, Action::NextView),
+            (KeyCode::BackTab, KeyModifiers::SHIFT, Action::PrevView),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL, Action::ZoneSelect),
+            (KeyCode::Char('g'), KeyModifiers::CONTROL, Action::ZoneGroup),
+            (KeyCode::Char('z'), KeyModifiers::ALT, Action::ZonesOverview),
+            (KeyCode::Char(' '), KeyModifiers::CONTROL, Action::PlayPause),
+            (KeyCode::Char('p'), KeyModifiers::CONTROL, Action::PlayPause),
+            (KeyCode::Char('e'), KeyModifiers::CONTROL, Action::PauseOnTrackEnd),
+            (KeyCode::Up, KeyModifiers::CONTROL, Action::VolumeUp),
+            (KeyCode::Down, KeyModifiers::CONTROL, Action::VolumeDown),
+            (KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::SHIFT, Action::VolumeUpCoarse),
+            (KeyCode::Down, KeyModifiers::CONTROL | KeyModifiers::SHIFT, Action::VolumeDownCoarse),
+            (KeyCode::Right, KeyModifiers::CONTROL, Action::NextTrack),
+            (KeyCode::Left, KeyModifiers::CONTROL, Action::PrevTrack),
+            (KeyCode::Char('q'), KeyModifiers::CONTROL, Action::QueueMode),
+            (KeyCode::Char('a'), KeyModifiers::CONTROL, Action::QueueAppend),
+            (KeyCode::Delete, KeyModifiers::CONTROL, Action::QueueClear),
+            (KeyCode::Char('q'), KeyModifiers::ALT, Action::QueueToggle),
+            (KeyCode::Char('c'), KeyModifiers::ALT, Action::CompactToggle),
+            (KeyCode::Char('f'), KeyModifiers::CONTROL, Action::Search),
+            (KeyCode::Char('r'), KeyModifiers::CONTROL, Action::Reconnect),
+            (KeyCode::Left, KeyModifiers::ALT, Action::SplitLeft),
+            (KeyCode::Right, KeyModifiers::ALT, Action::SplitRight),
+            (KeyCode::Char('h'), KeyModifiers::CONTROL, Action::Help),
+            (KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Quit),
+        ];
+        let bindings = defaults.into_iter()
+            .map(|(code, modifiers, action)| ((code, modifiers), action))
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    pub fn load(path: &Path) -> Self {
+        let mut keymap = Self::default();
+
+        if let Ok(content) = fs::read_to_string(path) {
+            match toml::from_str::<HashMap<Action, KeySpec>>(&content) {
+                Ok(user_bindings) => keymap.apply(user_bindings),
+                Err(err) => log::warn!("Ignoring {}: {}", path.display(), err),
+            }
+        }
+
+        keymap
+    }
+
+    fn apply(&mut self, user_bindings: HashMap<Action, KeySpec>) {
+        for (action, key_spec) in user_bindings {
+            let keys = match key_spec {
+                KeySpec::One(key) => vec![key],
+                KeySpec::Many(keys) => keys,
+            };
+
+            // Bound keys replace the default keys of the action
+            self.bindings.retain(|_, bound_action| *bound_action != action);
+
+            for key in keys {
+                match parse_key(&key) {
+                    // Plain characters are typed input, like the jump in Browse and the filters
+                    Some((KeyCode::Char(_), modifiers)) if (modifiers - KeyModifiers::SHIFT).is_empty() => {
+                        log::warn!("Ignoring key '{}' for {:?}, characters need a ctrl- or alt- modifier", key, action);
+                    }
+                    Some(key) => {
+                        self.bindings.insert(key, action);
+                    }
+                    None => log::warn!("Ignoring unknown key '{}' for {:?}", key, action),
+                }
+            }
+        }
+    }
+
+    pub fn get_action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings.get(&(key.code, key.modifiers)).copied()
+    }
+
+    pub fn get_key_names(&self, action: Action) -> Vec<String> {
+        let mut key_names = self.bindings.iter()
+            .filter_map(|((code, modifiers), bound_action)| {
+                if *bound_action == action {
+                    Some(get_key_name(code, modifiers))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        key_names.sort();
+        key_names
+    }
+}
+
+fn parse_key(key: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = key;
+
+    loop {
+        let lower = rest.to_ascii_lowercase();
+
+        if lower.starts_with("ctrl-") && rest.len() > 5 {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = &rest[5..];
+        } else if lower.starts_with("alt-") && rest.len() > 4 {
+            modifiers |= KeyModifiers::ALT;
+            rest = &rest[4..];
+        } else if lower.starts_with("shift-") && rest.len() > 6 {
+            modifiers |= KeyModifiers::SHIFT;
+            rest = &rest[6..];
+        } else {
+            break;
+        }
+    }
+
+    let code = match rest.to_ascii_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "tab" => {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                KeyCode::BackTab
+            } else {
+                KeyCode::Tab
+            }
+        }
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "delete" => KeyCode::Delete,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        lower => {
+            let mut chars = rest.chars();
+            let first = chars.next()?;
+
+            if chars.next().is_none() {
+                KeyCode::Char(first)
+            } else if let Some(number) = lower.strip_prefix('f') {
+                KeyCode::F(number.parse().ok()?)
+            } else {
+                return None;
+            }
+        }
+    };
+
+    Some((code, modifiers))
+}
+
+fn get_key_name(code: &KeyCode, modifiers: &KeyModifiers) -> String {
+    let mut name = String::new();
+
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl-");
+    }
+
+    if modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt-");
+    }
+
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        name.push_str("Sh-");
+    }
+
+    match code {
+        KeyCode::Up => name.push_str("Up"),
+        KeyCode::Down => name.push_str("Dn"),
+        KeyCode::Left => name.push_str("Le"),
+        KeyCode::Right => name.push_str("Ri"),
+        KeyCode::Home => name.push_str("Hm"),
+        KeyCode::End => name.push_str("End"),
+        KeyCode::PageUp => name.push_str("PgUp"),
+        KeyCode::PageDown => name.push_str("PgDn"),
+        KeyCode::Tab | KeyCode::BackTab => name.push_str("Tab"),
+        KeyCode::Enter => name.push_str("Enter"),
+        KeyCode::Esc => name.push_str("Esc"),
+        KeyCode::Delete => name.push_str("Del"),
+        KeyCode::Backspace => name.push_str("Backsp"),
+        KeyCode::Char(' ') => name.push_str("Sp"),
+        KeyCode::Char(c) => name.push(*c),
+        KeyCode::F(number) => name.push_str(&format!("F{}", number)),
+        _ => name.push('?'),
+    }
+
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_keymap(content: &str) -> Keymap {
+        let mut keymap = Keymap::default();
+
+        keymap.apply(toml::from_str(content).unwrap());
+        keymap
+    }
+
+    fn get_action(keymap: &Keymap, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        keymap.get_action(&KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn bound_keys_replace_the_defaults() {
+        let keymap = create_keymap(r#"
+            play_pause = ["ctrl-o", "alt-shift-p"]
+            next_track = "f9"
+        "#);
+
+        assert_eq!(get_action(&keymap, KeyCode::Char('o'), KeyModifiers::CONTROL), Some(Action::PlayPause));
+        assert_eq!(get_action(&keymap, KeyCode::Char('p'), KeyModifiers::ALT | KeyModifiers::SHIFT), Some(Action::PlayPause));
+        assert_eq!(get_action(&keymap, KeyCode::Char('p'), KeyModifiers::CONTROL), None);
+        assert_eq!(get_action(&keymap, KeyCode::F(9), KeyModifiers::NONE), Some(Action::NextTrack));
+        assert_eq!(get_action(&keymap, KeyCode::Right, KeyModifiers::CONTROL), None);
+    }
+
+    #[test]
+    fn plain_characters_are_not_bound() {
+        let keymap = create_keymap(r#"
+            play_pause = ["p", "shift-p", "space", "ctrl-o"]
+            next_track = "n"
+        "#);
+
+        assert_eq!(get_action(&keymap, KeyCode::Char('p'), KeyModifiers::NONE), None);
+        assert_eq!(get_action(&keymap, KeyCode::Char('p'), KeyModifiers::SHIFT), None);
+        assert_eq!(get_action(&keymap, KeyCode::Char(' '), KeyModifiers::NONE), None);
+        assert_eq!(get_action(&keymap, KeyCode::Char('o'), KeyModifiers::CONTROL), Some(Action::PlayPause));
+        assert_eq!(get_action(&keymap, KeyCode::Char('n'), KeyModifiers::NONE), None);
+    }
+}
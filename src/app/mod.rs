@@ -1,16 +1,47 @@
 use any_ascii::any_ascii;
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use chrono::NaiveTime;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
 use roon_api::{
     browse,
-    transport::{Control, QueueItem, QueueOperation, QueueChange, Zone, ZoneSeek, volume}
+    transport::{Control, QueueItem, QueueOperation, QueueChange, Seek, State, Zone, ZoneSeek, volume}
 };
+use std::{collections::{HashMap, HashSet}, path::PathBuf, sync::{Arc, atomic::{AtomicBool, Ordering}}, time::{Duration, Instant}};
 use tokio::sync::mpsc;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::io::{EndPoint, IoEvent, QueueMode};
+use crate::io::{ConnectionState, EndPoint, IoEvent, QueueAction, QueueMode, ZoneEntry, ZoneSort, ZoneSummary};
+use crate::app::art::{AlbumArt, Protocol};
+use crate::app::clipboard::Clipboard;
+use crate::app::history::SearchHistory;
+use crate::app::keymap::{Action, Keymap};
 use crate::app::stateful_list::StatefulList;
-
+use crate::app::theme::Theme;
+
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const SEEK_STEP: i64 = 10;
+const VOLUME_COARSE_FACTOR: i32 = 5;
+const SEEK_SETTLE_TIME: Duration = Duration::from_millis(1500);
+const QUEUE_LOAD_THRESHOLD: usize = 20;
+const MONITOR_MESSAGE: &str = "Monitor mode, controls are disabled";
+const ALARM_FORMAT: &str = "%H:%M";
+const SPLIT_DEFAULT: u16 = 50;
+const SPLIT_MIN: u16 = 20;
+const SPLIT_MAX: u16 = 80;
+const SPLIT_STEP: u16 = 5;
+const BROWSE_CACHE_LEVELS: usize = 4;
+const STATUS_DURATION: Duration = Duration::from_secs(4);
+const ERROR_STATUS_DURATION: Duration = Duration::from_secs(8);
+
+pub mod art;
+mod clipboard;
+mod export;
+mod fuzzy;
+pub mod history;
+pub mod keymap;
 pub mod ui;
 pub mod stateful_list;
+pub mod theme;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum AppReturn {
@@ -18,6 +49,32 @@ pub enum AppReturn {
     Continue,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayoutMode {
+    Horizontal,
+    Vertical,
+    Auto,
+}
+
+pub struct AppOptions {
+    pub no_unicode_symbols: bool,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    pub search_history: SearchHistory,
+    pub no_art: bool,
+    pub volume_step: i32,
+    pub volume_bar: bool,
+    pub no_confirm: bool,
+    pub monitor: bool,
+    pub export_dir: PathBuf,
+    pub split: Option<u16>,
+    pub layout_mode: LayoutMode,
+    pub no_queue: bool,
+    pub page_size: Option<usize>,
+    pub wrap: bool,
+    pub compact: bool,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum View {
     Browse = 0,
@@ -28,28 +85,96 @@ enum View {
     Grouping = 5,
     GroupingPreset = 6,
     Help = 7,
+    Bookmarks = 8,
+    Confirm = 9,
+    ZoneSettings = 10,
+    Overview = 11,
 }
 
 pub struct App {
     to_roon: mpsc::Sender<IoEvent>,
     from_roon: mpsc::Receiver<IoEvent>,
+    ticking: Arc<AtomicBool>,
+    overview_open: Arc<AtomicBool>,
     no_unicode_symbols: bool,
+    volume_step: i32,
+    volume_bar: bool,
+    no_confirm: bool,
+    monitor: bool,
+    compact: bool,
+    keymap: Keymap,
+    theme: Theme,
+    album_art: Option<AlbumArt>,
+    art_area: Rect,
+    clipboard: Clipboard,
+    connection: ConnectionState,
     core_name: Option<String>,
     selected_view: Option<View>,
     prev_view: Option<View>,
+    full_screen: bool,
+    layout_mode: LayoutMode,
+    vertical_layout: bool,
+    queue_hidden: bool,
     browse: StatefulList<browse::Item>,
     browse_match_list: Vec<usize>,
+    browse_input_matches: Option<(String, Vec<usize>)>,
+    browse_filter: Option<String>,
+    browse_loading: bool,
+    browse_unfiltered: Option<Vec<browse::Item>>,
+    browse_breadcrumb: Vec<String>,
+    // The items and selection of the levels above, by level
+    browse_cache: Vec<(usize, Vec<browse::Item>, Option<usize>)>,
+    browse_restore: Option<usize>,
+    bookmarks: StatefulList<String>,
     pending_item_key: Option<String>,
+    search_history: SearchHistory,
+    volume_range: Option<(f32, f32)>,
+    queue_export: bool,
+    sleep_timer_prompt: bool,
+    alarm_prompt: bool,
+    edit_preset: Option<String>,
+    export_dir: PathBuf,
     prompt: String,
     input: String,
     cursor_position: usize,
     max_input_len: usize,
-    zones: StatefulList<(EndPoint, String)>,
+    zones: StatefulList<ZoneEntry>,
+    zone_filter: String,
+    zone_match_list: Vec<usize>,
+    zones_unfiltered: Option<Vec<ZoneEntry>>,
+    zone_sort: Option<&'static str>,
+    remaining_time: bool,
+    // Width of the Browse view in percent, the Queue view takes the rest
+    split: u16,
+    split_fixed: bool,
+    expanded_zones: HashSet<String>,
+    zone_settings: StatefulList<(String, bool)>,
+    zones_overview: StatefulList<ZoneSummary>,
+    zone_transfer: bool,
     selected_zone: Option<Zone>,
     zone_seek: Option<ZoneSeek>,
+    zone_seek_received: Instant,
+    gauge_area: Rect,
+    last_click: Option<(Instant, u16)>,
+    last_seek: Option<(Instant, i64)>,
+    marquee_start: Instant,
     grouping: StatefulList<(String, String, bool)>,
+    output_volumes: HashMap<String, volume::Volume>,
     queue: StatefulList<QueueItem>,
+    queue_duration: u32,
+    queue_positions: HashMap<String, usize>,
+    queue_restore_pending: bool,
+    // The loaded items are the complete queue, changes can't be skipped
+    queue_complete: bool,
+    queue_loading: bool,
+    spinner_start: Instant,
     pause_on_track_end: bool,
+    sleep_timer: Option<Instant>,
+    alarm: Option<String>,
+    message: Option<&'static str>,
+    // A transient status, whether it is an error and the time it disappears
+    status: Option<(String, bool, Instant)>,
+    confirm: Option<(&'static str, IoEvent)>,
     queue_mode: Option<&'static str>,
     matched_preset: Option<String>,
     matched_draft_preset: Option<String>,
@@ -57,27 +182,97 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(to_roon: mpsc::Sender<IoEvent>, from_roon: mpsc::Receiver<IoEvent>, no_unicode_symbols: bool) -> Self {
+    pub fn new(
+        to_roon: mpsc::Sender<IoEvent>,
+        from_roon: mpsc::Receiver<IoEvent>,
+        ticking: Arc<AtomicBool>,
+        overview_open: Arc<AtomicBool>,
+        options: AppOptions,
+    ) -> Self {
+        let album_art = if options.no_art {None} else {Some(AlbumArt::new(Protocol::detect()))};
+        let page_size = options.page_size;
+        let wrap = options.wrap;
+
         Self {
             to_roon,
             from_roon,
-            no_unicode_symbols,
+            ticking,
+            overview_open,
+            no_unicode_symbols: options.no_unicode_symbols,
+            volume_step: options.volume_step,
+            volume_bar: options.volume_bar,
+            no_confirm: options.no_confirm,
+            monitor: options.monitor,
+            compact: options.compact,
+            keymap: options.keymap,
+            theme: options.theme,
+            album_art,
+            art_area: Rect::default(),
+            clipboard: Clipboard::default(),
+            connection: ConnectionState::default(),
             core_name: None,
             selected_view: None,
             prev_view: None,
-            browse: StatefulList::new(),
+            full_screen: false,
+            layout_mode: options.layout_mode,
+            vertical_layout: options.layout_mode == LayoutMode::Vertical,
+            queue_hidden: options.no_queue,
+            browse: StatefulList::with_navigation(page_size, wrap),
             browse_match_list: Vec::new(),
+            browse_input_matches: None,
+            browse_filter: None,
+            browse_loading: false,
+            browse_unfiltered: None,
+            browse_breadcrumb: Vec::new(),
+            browse_cache: Vec::new(),
+            browse_restore: None,
+            bookmarks: StatefulList::with_navigation(page_size, wrap),
             pending_item_key: None,
+            search_history: options.search_history,
+            volume_range: None,
+            queue_export: false,
+            sleep_timer_prompt: false,
+            alarm_prompt: false,
+            edit_preset: None,
+            export_dir: options.export_dir,
             prompt: String::new(),
             input: String::new(),
             cursor_position: 0,
             max_input_len: 0,
-            zones: StatefulList::new(),
+            zones: StatefulList::with_navigation(page_size, wrap),
+            zone_filter: String::new(),
+            zone_match_list: Vec::new(),
+            zones_unfiltered: None,
+            zone_sort: None,
+            remaining_time: false,
+            split: options.split.unwrap_or(SPLIT_DEFAULT),
+            split_fixed: options.split.is_some(),
+            expanded_zones: HashSet::new(),
+            zone_settings: StatefulList::with_navigation(page_size, wrap),
+            zones_overview: StatefulList::with_navigation(page_size, wrap),
+            zone_transfer: false,
             selected_zone: None,
             zone_seek: None,
-            grouping: StatefulList::new(),
-            queue: StatefulList::new(),
+            zone_seek_received: Instant::now(),
+            gauge_area: Rect::default(),
+            last_click: None,
+            last_seek: None,
+            marquee_start: Instant::now(),
+            grouping: StatefulList::with_navigation(page_size, wrap),
+            output_volumes: HashMap::new(),
+            queue: StatefulList::with_navigation(page_size, wrap),
+            queue_duration: 0,
+            queue_positions: HashMap::new(),
+            queue_restore_pending: false,
+            queue_complete: false,
+            queue_loading: false,
+            spinner_start: Instant::now(),
             pause_on_track_end: false,
+            sleep_timer: None,
+            alarm: None,
+            message: None,
+            status: None,
+            confirm: None,
             queue_mode: None,
             matched_preset: None,
             matched_draft_preset: None,
@@ -91,8 +286,36 @@ impl App {
                 IoEvent::Input(key) => {
                     return self.do_action(key).await;
                 }
-                IoEvent::CoreName(name) => {
-                    self.core_name = name;
+                IoEvent::Mouse(mouse) => {
+                    return self.handle_mouse_event(mouse).await;
+                }
+                IoEvent::Paste(text) => {
+                    match self.selected_view {
+                        Some(View::Prompt) | Some(View::GroupingPreset) => self.enter_str(&text),
+                        _ => (),
+                    }
+                }
+                IoEvent::Connection(connection) => {
+                    self.core_name = match &connection {
+                        ConnectionState::Found(name) => Some(name.to_owned()),
+                        _ => None,
+                    };
+                    self.connection = connection;
+                }
+                IoEvent::Message(message) => self.message = Some(message),
+                IoEvent::Status(status) => self.set_status(status, false),
+                IoEvent::BrowseMessage(message, is_error) => self.set_status(message, is_error),
+                IoEvent::Tick => {
+                    let expired = self.status.as_ref().map(|(_, _, until)| Instant::now() >= *until).unwrap_or_default();
+
+                    if expired {
+                        self.status = None;
+                        self.update_ticking();
+                    }
+                }
+                IoEvent::BrowseBreadcrumb(browse_breadcrumb) => {
+                    self.update_browse_cache(browse_breadcrumb.len());
+                    self.browse_breadcrumb = browse_breadcrumb;
                 }
                 IoEvent::BrowseTitle(browse_title) => {
                     if self.selected_view.is_none() {
@@ -102,8 +325,23 @@ impl App {
                     self.browse.title = Some(browse_title);
                 }
                 IoEvent::BrowseList(offset, mut items) => {
+                    if let Some(browse_items) = self.browse_unfiltered.as_mut() {
+                        if offset > 0 && offset == browse_items.len() {
+                            // Keep filtering while more items are loaded
+                            browse_items.append(&mut items);
+                            self.apply_browse_filter();
+
+                            return AppReturn::Continue;
+                        }
+
+                        self.browse_filter = None;
+                        self.browse.items = self.browse_unfiltered.take();
+                        self.browse_match_list.clear();
+                    }
+
                     if offset == 0 {
                         self.browse.items = Some(items);
+                        self.browse_input_matches = None;
 
                         if let Some(view) = self.selected_view.as_ref() {
                             if *view == View::Browse {
@@ -117,20 +355,78 @@ impl App {
                             // Refresh paging
                             self.browse.select_first();
                         } else {
-                            self.to_roon.send(IoEvent::BrowseRefresh).await.unwrap();
+                            return self.send_to_roon(IoEvent::BrowseRefresh).await;
+                        }
+                    }
+
+                    if let (Some(index), Some(View::Browse)) = (self.browse_restore, self.selected_view.as_ref()) {
+                        if index < self.browse.items.as_ref().map(|items| items.len()).unwrap_or_default() {
+                            self.browse.select(Some(index));
                         }
                     }
                 }
-                IoEvent::QueueList(queue_list) => {
-                    self.to_roon.send(IoEvent::QueueListLast(queue_list.last().cloned())).await.unwrap();
+                IoEvent::BrowseSearchPrompt(item) => {
+                    if let Some(prompt) = item.input_prompt.as_ref() {
+                        // Return to Browse when the search is done or cancelled
+                        if self.selected_view != Some(View::Browse) {
+                            self.select_view(Some(View::Browse));
+                        }
+
+                        self.prompt = prompt.prompt.to_owned();
+                        self.pending_item_key = item.item_key;
+                        self.select_view(Some(View::Prompt));
+                    }
+                }
+                IoEvent::BrowseLoading(loading) => {
+                    if !loading {
+                        self.browse_restore = None;
+                    }
+
+                    self.set_loading(loading, self.queue_loading);
+                }
+                IoEvent::QueueLoading(loading) => {
+                    self.set_loading(self.browse_loading, loading);
+                }
+                IoEvent::QueueList(queue_list, complete) => {
+                    let last = queue_list.last().cloned();
+                    let selected = self.get_queue_item_id();
+
                     self.queue.items = Some(queue_list);
+                    self.queue_complete = complete;
+                    self.set_loading(self.browse_loading, false);
+                    self.update_queue_duration();
+
+                    if self.queue.is_selected() {
+                        // Follow the selected item, it can have moved when the queue was out of sync
+                        let index = self.queue.items.as_ref()
+                            .and_then(|items| items.iter().position(|item| Some(item.queue_item_id) == selected));
+
+                        // Refresh paging, more items can have been loaded
+                        self.queue.select(index.or(self.queue.state.selected()));
+                    }
+
+                    if self.queue_restore_pending {
+                        self.queue_restore_pending = false;
+                        self.restore_queue_position();
+                    }
+
+                    return self.send_to_roon(IoEvent::QueueListLast(last)).await;
                 }
                 IoEvent::QueueListChanges(changes) => {
-                    let selected = self.get_queue_select_string();
-                    self.apply_queue_changes(&changes, selected);
+                    // Titles can repeat in the queue, the id identifies the selected item
+                    let selected = self.get_queue_item_id();
+                    let skipped = self.apply_queue_changes(&changes, selected).unwrap_or_default();
+                    self.update_queue_duration();
+
+                    if skipped && self.queue_complete {
+                        // Replace the local copy, the QueueList that follows selects by id
+                        if self.send_to_roon(IoEvent::QueueResync).await == AppReturn::Exit {
+                            return AppReturn::Exit;
+                        }
+                    }
 
                     if let Some(items) = self.queue.items.as_ref() {
-                        self.to_roon.send(IoEvent::QueueListLast(items.last().cloned())).await.unwrap();
+                        return self.send_to_roon(IoEvent::QueueListLast(items.last().cloned())).await;
                     }
                 }
                 IoEvent::QueueModeCurrent(queue_mode) => {
@@ -142,15 +438,44 @@ impl App {
                     };
                     self.queue_mode = queue_mode;
                 }
+                IoEvent::Bookmarks(bookmarks) => {
+                    let selected = self.bookmarks.get_selected_item().cloned();
+
+                    self.bookmarks.items = Some(bookmarks);
+
+                    if self.selected_view == Some(View::Bookmarks) {
+                        let index = self.bookmarks.items.as_ref()
+                            .and_then(|items| items.iter().position(|name| Some(name) == selected.as_ref()));
+
+                        self.bookmarks.select(index);
+                    }
+                }
+                IoEvent::RemainingTime(remaining_time) => self.remaining_time = remaining_time,
+                // The split given on the command line takes precedence over the stored one
+                IoEvent::Split(split) if !self.split_fixed => self.split = split.clamp(SPLIT_MIN, SPLIT_MAX),
+                IoEvent::ZoneSortCurrent(zone_sort) => {
+                    let zone_sort = match zone_sort {
+                        ZoneSort::Alphabetical => None,
+                        ZoneSort::RecentlyUsed => Some("Recently used"),
+                        ZoneSort::PlayingFirst => Some("Playing first"),
+                    };
+                    self.zone_sort = zone_sort;
+                }
                 IoEvent::Zones(zones) => {
-                    self.zones.items = Some(zones);
+                    if self.zones_unfiltered.is_some() {
+                        // Keep filtering the updated list
+                        self.zones_unfiltered = Some(zones);
+                        self.apply_zone_filter();
+                    } else {
+                        self.zones.items = Some(zones);
+                    }
 
-                    if self.selected_view == Some(View::Zones) {
+                    if self.selected_view == Some(View::Zones) && self.zones_unfiltered.is_none() {
                         let index = if let Some(zone) = &self.selected_zone {
                             if let Some(items) = self.zones.items.as_ref() {
                                 items
                                     .iter()
-                                    .position(|(end_point, _)| {
+                                    .position(|(end_point, _, _, _, _)| {
                                         match end_point {
                                             EndPoint::Zone(zone_id) => *zone_id == zone.zone_id,
                                             _ => false,
@@ -169,7 +494,7 @@ impl App {
                 IoEvent::ZoneSelect => {
                     if let Some(items) = self.zones.items.as_ref() {
                         let has_zones = items.iter()
-                            .any(|(endpoint, _)| {
+                            .any(|(endpoint, _, _, _, _)| {
                                 match endpoint {
                                     EndPoint::Zone(_) => true,
                                     _ => false,
@@ -185,31 +510,110 @@ impl App {
                     self.select_view(Some(View::Zones));
                 }
                 IoEvent::ZoneChanged(zone) => {
+                    let zone_switched = match self.selected_zone.as_ref() {
+                        Some(selected_zone) => selected_zone.zone_id != zone.zone_id,
+                        None => true,
+                    };
+
+                    if zone_switched {
+                        if self.queue.is_selected() {
+                            self.save_queue_position();
+                        }
+
+                        // The queue of the new zone is received next
+                        self.queue_restore_pending = true;
+                    }
+
+                    let title = zone.now_playing.as_ref().map(|now_playing| &now_playing.three_line.line1);
+                    let prev_title = self.selected_zone.as_ref()
+                        .and_then(|zone| zone.now_playing.as_ref())
+                        .map(|now_playing| &now_playing.three_line.line1);
+
+                    if title != prev_title {
+                        // Restart scrolling of long lines for the new track
+                        self.marquee_start = Instant::now();
+                    }
+
+                    if let Some(album_art) = self.album_art.as_mut() {
+                        let image_key = zone.now_playing.as_ref()
+                            .and_then(|now_playing| now_playing.image_key.as_deref());
+
+                        album_art.set_image_key(image_key);
+                    }
+
                     self.selected_zone = Some(zone);
+                    self.update_ticking();
+
+                    if self.selected_view == Some(View::ZoneSettings) {
+                        self.zone_settings.items = self.get_zone_settings();
+                    }
 
                     if self.pending_item_key.is_some() {
-                        self.to_roon.send(IoEvent::BrowseSelected(self.pending_item_key.take())).await.unwrap();
+                        let item_key = self.pending_item_key.take();
+
+                        return self.send_to_roon(IoEvent::BrowseSelected(item_key)).await;
+                    }
+                }
+                IoEvent::ZoneRemoved(_) => {
+                    if let Some(album_art) = self.album_art.as_mut() {
+                        album_art.set_image_key(None);
+                    }
+
+                    self.selected_zone = None;
+                    self.update_ticking();
+                }
+                IoEvent::ZoneSeek(seek) => {
+                    self.zone_seek = Some(seek);
+                    self.zone_seek_received = Instant::now();
+                }
+                IoEvent::ZonesOverview(summaries) => {
+                    // Follow the selected zone through the reordering of the list
+                    let selected = self.zones_overview.get_selected_item().map(|summary| summary.zone_id.to_owned());
+                    let prev_index = self.zones_overview.state.selected();
+
+                    self.zones_overview.items = Some(summaries);
+
+                    if self.selected_view == Some(View::Overview) {
+                        let len = self.zones_overview.items.as_ref().map(|items| items.len()).unwrap_or_default();
+                        let index = self.zones_overview.items.as_ref()
+                            .and_then(|items| items.iter().position(|summary| Some(&summary.zone_id) == selected.as_ref()))
+                            .or_else(|| prev_index.filter(|_| len > 0).map(|index| index.min(len - 1)));
+
+                        self.zones_overview.state.select(index);
                     }
                 }
-                IoEvent::ZoneRemoved(_) => self.selected_zone = None,
-                IoEvent::ZoneSeek(seek) => self.zone_seek = Some(seek),
                 IoEvent::ZoneGrouping(grouping) => {
+                    let edit_preset = self.edit_preset.take();
+
                     if let Some(grouping) = grouping.as_ref() {
                         if !grouping.is_empty() {
                             match self.selected_view.as_ref() {
                                 Some(View::Prompt) => self.restore_view(),
                                 Some(View::Zones) => self.restore_view(),
                                 Some(View::Help) => self.restore_view(),
+                                Some(View::Bookmarks) => self.restore_view(),
+                                Some(View::ZoneSettings) => self.restore_view(),
+                                Some(View::Overview) => self.restore_view(),
+                                Some(View::Confirm) => self.restore_view(),
                                 _ => (),
                             }
 
                             self.matched_draft_preset = self.matched_preset.to_owned();
+
+                            // An edited preset is saved again under its own name
+                            if let Some(preset) = edit_preset {
+                                self.input = preset.to_owned();
+                                self.move_cursor_end();
+                                self.matched_draft_preset = Some(preset);
+                            }
+
                             self.select_view(Some(View::Grouping));
                         }
                     }
 
                     self.grouping.items = grouping;
                 }
+                IoEvent::OutputVolumes(output_volumes) => self.output_volumes = output_volumes,
                 IoEvent::ZonePresetMatched(matched_preset) => {
                     if self.draft_match {
                         self.draft_match = false;
@@ -219,120 +623,164 @@ impl App {
                     }
                 }
                 IoEvent::PauseOnTrackEndActive(pause_on_track_end) => self.pause_on_track_end = pause_on_track_end,
+                IoEvent::SleepTimerActive(sleep_timer) => self.sleep_timer = sleep_timer,
+                IoEvent::AlarmActive(alarm) => self.alarm = alarm,
+                IoEvent::ImageData(data) => {
+                    if let Some(album_art) = self.album_art.as_mut() {
+                        album_art.set_image_data(&data);
+                    }
+                }
+                IoEvent::Redraw => {
+                    // The terminal might have been cleared
+                    if let Some(album_art) = self.album_art.as_mut() {
+                        album_art.invalidate();
+                    }
+                }
                 _ => ()
             }
+        } else {
+            log::warn!("Roon channel closed, exiting");
+
+            return AppReturn::Exit;
         }
 
         AppReturn::Continue
     }
 
-    fn get_queue_select_string(&self) -> Option<String> {
-        let index = self.queue.state.selected()?;
-        let selected = self.queue.items.as_ref()?.get(index)?.two_line.line1.to_owned();
+    async fn send_to_roon(&mut self, io_event: IoEvent) -> AppReturn {
+        if self.monitor && io_event.is_control() {
+            self.message = Some(MONITOR_MESSAGE);
+
+            return AppReturn::Continue;
+        }
+
+        match self.to_roon.send(io_event).await {
+            Ok(()) => AppReturn::Continue,
+            Err(err) => {
+                log::error!("Failed to send to Roon: {}, exiting", err);
 
-        Some(selected)
+                AppReturn::Exit
+            }
+        }
     }
 
-    fn apply_queue_changes(&mut self, changes: &Vec<QueueChange>, selected: Option<String>) -> Option<()> {
+    // Returns true when changes beyond the loaded part of the queue were skipped
+    fn apply_queue_changes(&mut self, changes: &[QueueChange], selected: Option<u32>) -> Option<bool> {
         let queue = self.queue.items.as_mut()?;
+        let skipped = apply_queue_changes(queue, changes)?;
 
-        for change in changes {
-            match change.operation {
-                QueueOperation::Insert => {
-                    for i in 0..change.items.as_ref()?.len() {
-                        let item = change.items.as_ref()?.get(i)?;
-
-                        queue.insert(change.index + i, item.to_owned());
-                    }
-                }
-                QueueOperation::Remove => {
-                    for _ in 0..change.count? {
-                        queue.remove(change.index);
-                    }
-                }
-            }
+        if skipped {
+            log::warn!("Skipped queue changes beyond the {} loaded items", queue.len());
         }
 
         if let Some(selected) = selected {
-            let index = queue.iter().position(|item| item.two_line.line1 == selected);
+            let index = queue.iter().position(|item| item.queue_item_id == selected);
 
             self.queue.select(index);
         };
 
-        Some(())
+        Some(skipped)
     }
 
     fn select_view(&mut self, view: Option<View>) {
+        if self.selected_view == Some(View::Queue) {
+            self.save_queue_position();
+        } else if self.selected_view == Some(View::Browse) && view != Some(View::Browse) {
+            self.clear_browse_filter();
+        } else if self.selected_view == Some(View::Zones) {
+            self.clear_zone_filter();
+            self.zone_transfer = false;
+        } else if self.selected_view == Some(View::Confirm) {
+            // Leaving the confirmation cancels the pending action
+            self.confirm = None;
+        }
+
         self.prev_view = self.selected_view.take();
 
+        self.deselect_all_except(view.as_ref());
+
         match &view {
-            Some(view) => {
-                match view {
-                    View::Browse => {
-                        self.browse.select(None);
-                        self.queue.deselect();
-                        self.zones.deselect();
-                        self.grouping.deselect();
-                    }
-                    View::Queue => {
-                        self.browse.deselect();
-                        self.queue.select(None);
-                        self.zones.deselect();
-                        self.grouping.deselect();
-                    }
-                    View::Zones => {
-                        let index = if let Some(zone) = &self.selected_zone {
-                            if let Some(items) = self.zones.items.as_ref() {
-                                items
-                                    .iter()
-                                    .position(|(end_point, _)| {
-                                        match end_point {
-                                            EndPoint::Zone(zone_id) => *zone_id == zone.zone_id,
-                                            _ => false,
-                                        }
-                                    })
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        };
+            Some(View::Browse) => self.browse.select(None),
+            Some(View::Queue) => {
+                let index = self.get_saved_queue_position();
 
-                        self.zones.select(index);
-                        self.queue.deselect();
-                        self.browse.deselect();
-                        self.grouping.deselect();
-                    }
-                    View::Grouping => {
-                        self.grouping.select(None);
-                        self.browse.deselect();
-                        self.queue.deselect();
-                        self.zones.deselect();
-                    }
-                    _  => {
-                        self.browse.deselect();
-                        self.queue.deselect();
-                        self.zones.deselect();
-                        self.grouping.deselect();
+                self.queue.select(index);
+            }
+            Some(View::Zones) => {
+                let index = if let Some(zone) = &self.selected_zone {
+                    if let Some(items) = self.zones.items.as_ref() {
+                        items
+                            .iter()
+                            .position(|(end_point, _, _, _, _)| {
+                                match end_point {
+                                    EndPoint::Zone(zone_id) => *zone_id == zone.zone_id,
+                                    _ => false,
+                                }
+                            })
+                    } else {
+                        None
                     }
+                } else {
+                    None
                 };
+
+                self.zones.select(index);
             }
-            None => {
-                self.browse.deselect();
-                self.queue.deselect();
-                self.zones.deselect();
-                self.grouping.deselect();
+            Some(View::Grouping) => self.grouping.select(None),
+            Some(View::Bookmarks) => self.bookmarks.select(None),
+            Some(View::ZoneSettings) => self.zone_settings.select(None),
+            Some(View::Overview) => {
+                let index = self.get_overview_index();
+
+                self.zones_overview.select(index);
             }
+            _ => (),
         }
 
+        // Roon only sends the zones overview while it is shown
+        self.overview_open.store(view == Some(View::Overview), Ordering::Relaxed);
         self.selected_view = view;
     }
 
+    fn deselect_all_except(&mut self, view: Option<&View>) {
+        if view != Some(&View::Browse) {
+            self.browse.deselect();
+        }
+        if view != Some(&View::Queue) {
+            self.queue.deselect();
+        }
+        if view != Some(&View::Zones) {
+            self.zones.deselect();
+        }
+        if view != Some(&View::Grouping) {
+            self.grouping.deselect();
+        }
+        if view != Some(&View::Bookmarks) {
+            self.bookmarks.deselect();
+        }
+        if view != Some(&View::ZoneSettings) {
+            self.zone_settings.deselect();
+        }
+        if view != Some(&View::Overview) {
+            self.zones_overview.deselect();
+        }
+    }
+
+    fn get_view_order(&self) -> Vec<View> {
+        if self.queue_hidden {
+            vec![View::Browse, View::NowPlaying]
+        } else {
+            vec![View::Browse, View::Queue, View::NowPlaying]
+        }
+    }
+
     fn select_next_view(&mut self) {
-        let view_order = vec![View::Browse, View::Queue, View::NowPlaying];
+        let view_order = self.get_view_order();
         let next = match self.selected_view.as_ref() {
             Some(selected_view) => {
-                view_order.get(selected_view.to_owned() as usize + 1)
+                view_order.iter()
+                    .position(|view| view == selected_view)
+                    .and_then(|index| view_order.get(index + 1))
             }
             None => return,
         };
@@ -342,10 +790,12 @@ impl App {
     }
 
     fn select_prev_view(&mut self) {
-        let view_order = vec![View::Browse, View::Queue, View::NowPlaying];
+        let view_order = self.get_view_order();
         let prev = match self.selected_view.as_ref() {
             Some(selected_view) => {
-                view_order.get((selected_view.to_owned() as usize).wrapping_sub(1))
+                view_order.iter()
+                    .position(|view| view == selected_view)
+                    .and_then(|index| view_order.get(index.wrapping_sub(1)))
             }
             None => return,
         };
@@ -382,16 +832,45 @@ impl App {
     }
 
     fn move_cursor_end(&mut self) {
-        self.cursor_position = self.input.len();
+        self.cursor_position = self.input.chars().count();
     }
 
     fn enter_char(&mut self, new_char: char) {
-        if self.input.len() < self.max_input_len {
-            self.input.insert(self.cursor_position, new_char);
+        let char_width = new_char.width().unwrap_or_default();
+
+        if self.input.width() + char_width <= self.max_input_len {
+            let byte_index = self.get_cursor_byte_index();
+
+            self.input.insert(byte_index, new_char);
             self.move_cursor_right();
         }
     }
 
+    fn enter_str(&mut self, new_str: &str) {
+        let mut width = self.input.width();
+        let to_insert: String = new_str
+            .chars()
+            .filter(|c| !c.is_control())
+            .take_while(|c| {
+                width += c.width().unwrap_or_default();
+                width <= self.max_input_len
+            })
+            .collect();
+        let byte_index = self.get_cursor_byte_index();
+
+        self.input.insert_str(byte_index, &to_insert);
+        self.cursor_position += to_insert.chars().count();
+    }
+
+    fn get_cursor_byte_index(&self) -> usize {
+        // The cursor position is a char index, convert it to a byte index
+        self.input
+            .char_indices()
+            .nth(self.cursor_position)
+            .map(|(index, _)| index)
+            .unwrap_or(self.input.len())
+    }
+
     fn delete_char(&mut self) {
         let is_not_cursor_leftmost = self.cursor_position != 0;
         if is_not_cursor_leftmost {
@@ -415,7 +894,15 @@ impl App {
     }
 
     fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.input.len())
+        new_cursor_pos.clamp(0, self.input.chars().count())
+    }
+
+    fn get_cursor_column(&self) -> usize {
+        self.input
+            .chars()
+            .take(self.cursor_position)
+            .map(|c| c.width().unwrap_or_default())
+            .sum()
     }
 
     fn reset_cursor(&mut self) {
@@ -427,188 +914,108 @@ impl App {
             return;
         }
 
-        if let Some(items) = self.browse.items.as_ref() {
-            let key = key.to_ascii_lowercase();
-            let input = format!("{}{}", self.input, key);
-            let split = if let Some(title) = self.browse.title.as_ref() {
-                title == "Artists" || title == "Composers"
-            } else {
-                false
-            };
-            let mut order: Vec<char> = ('['..='~').rev().collect();
-            order = [order, ('!'..='@').rev().collect()].concat();
-
-            let index = if self.browse_match_list.is_empty() {
-                items
-                    .iter()
-                    .position(|item| {
-                        let title = any_ascii(&item.title);
-
-                        while let Some(pop) = order.last() {
-                            let mut pop_matched = false;
-                            let mut matching = |sub: &str| {
-                                if let Some(first_char) = sub.chars().next().unwrap().to_lowercase().next() {
-                                    if first_char == *pop {
-                                        if first_char == key {
-                                            return true;
-                                        } else {
-                                            pop_matched = true;
-                                        }
-                                    }
-                                }
+        let key = key.to_ascii_lowercase();
+        let input = format!("{}{}", self.input, key);
+        let (matches, cycle) = self.get_input_matches(&input);
 
-                                false
-                            };
-                            let result = if split {
-                                title.split(' ')
-                                    .position(matching)
-                                    .is_some()
-                            } else {
-                                let title = title
-                                    .to_ascii_lowercase()
-                                    .replacen("the ", "", 1);
-                                matching(&title)
-                            };
+        if !matches.is_empty() {
+            let index = matches[cycle % matches.len()];
 
-                            if result {
-                                return result;
-                            } else if key == *pop || pop_matched {
-                                break;
-                            }
+            self.input = input;
+            self.browse_match_list.push(index);
+            self.browse.state.select(Some(index));
+            self.browse_input_matches = Some((self.input.clone(), matches));
+        }
+    }
 
-                            order.pop();
-                        }
+    // Returns the best matching items and the number of times a single character is repeated
+    fn get_input_matches(&self, input: &str) -> (Vec<usize>, usize) {
+        let items = match self.browse.items.as_ref() {
+            Some(items) => items,
+            None => return (Vec::new(), 0),
+        };
+        let split = if let Some(title) = self.browse.title.as_ref() {
+            title == "Artists" || title == "Composers"
+        } else {
+            false
+        };
+        let first = match input.chars().next() {
+            Some(first) => first,
+            None => return (Vec::new(), 0),
+        };
 
-                        false
-                    })
-            } else {
-                let skip = *self.browse_match_list.last().unwrap();
-                let position = items
-                    .iter()
-                    .skip(skip)
-                    .position(|item| {
-                        // Find an upcoming item with matching input
-                        let title = any_ascii(&item.title)
-                            .to_ascii_lowercase()
-                            .replacen("the ", "", 1);
-
-                        if split {
-                            title.split(' ')
-                                .position(|sub| sub.starts_with(input.as_str()))
-                                .is_some()
-                        } else {
-                            title.starts_with(input.as_str())
-                        }
-                    });
+        // Repeating the same character cycles through its best matches
+        let (pattern, cycle) = if input.chars().all(|c| c == first) {
+            (first.to_string(), input.len() - 1)
+        } else {
+            (input.to_owned(), 0)
+        };
 
-                if let Some(position) = position {
-                    Some(skip + position)
-                } else {
-                    None
-                }
-            };
+        (fuzzy::get_best_matches(items.iter().map(|item| item.title.as_str()), &pattern, split), cycle)
+    }
 
-            if index.is_some() {
-                self.input = input;
-                self.browse_match_list.push(index.unwrap());
-                self.browse.state.select(index);
-            }
-        }
+    // Moves to the next or previous match of the typed input, wrapping at the ends
+    fn select_input_match(&mut self, forward: bool) {
+        let matches = match self.browse_input_matches.as_ref() {
+            Some((input, matches)) if *input == self.input && !matches.is_empty() => matches,
+            _ => return,
+        };
+        let len = matches.len();
+        let position = matches.iter().position(|index| Some(*index) == self.browse.state.selected());
+        let position = match position {
+            Some(position) if forward => (position + 1) % len,
+            Some(position) => (position + len - 1) % len,
+            None => 0,
+        };
+
+        self.browse.state.select(Some(matches[position]));
     }
 
     async fn do_action(&mut self, key: KeyEvent) -> AppReturn {
         if key.kind == KeyEventKind::Press {
+            // Messages are shown until the next key press
+            self.message = None;
+
             // Create a clone of selected_view to prevent second handle call on updated view
             let selected_view = self.selected_view.clone();
 
-            // Global key codes
-            match key.modifiers {
-                KeyModifiers::NONE => {
-                    match key.code {
-                        KeyCode::Tab => {
-                            self.input.clear();
-                            self.browse_match_list.clear();
-                            self.select_next_view();
-                        }
-                        _ => {
-                            // Key codes specific to the active view
-                            if let Some(view) = selected_view.as_ref() {
-                                match *view {
-                                    View::NowPlaying => self.handle_now_playing_key_codes(key).await,
-                                    View::Queue => self.handle_queue_key_codes(key).await,
-                                    View::Zones => self.handle_zone_key_codes(key).await,
-                                    View::Grouping => {
-                                        self.handle_grouping_key_codes(key).await;
-                                    }
-                                    View::Help => self.restore_view(),
-                                    _ => (),
-                                }
-                            }
-                        }
-                    }
-                }
-                KeyModifiers::SHIFT => {
-                    match key.code {
-                        KeyCode::BackTab => {
-                            self.input.clear();
-                            self.browse_match_list.clear();
-                            self.select_prev_view();
-                        }
-                        _ => (),
-                    }
-                }
-                KeyModifiers::CONTROL => {
-                    match key.code {
-                        KeyCode::Up => self.to_roon.send(IoEvent::ChangeVolume(1)).await.unwrap(),
-                        KeyCode::Down => self.to_roon.send(IoEvent::ChangeVolume(-1)).await.unwrap(),
-                        KeyCode::Left => self.to_roon.send(IoEvent::Control(Control::Previous)).await.unwrap(),
-                        KeyCode::Right => self.to_roon.send(IoEvent::Control(Control::Next)).await.unwrap(),
-                        KeyCode::Delete => self.to_roon.send(IoEvent::QueueClear).await.unwrap(),
-                        KeyCode::Char('e') => self.to_roon.send(IoEvent::PauseOnTrackEndReq).await.unwrap(),
-                        KeyCode::Char('p') | KeyCode::Char(' ') => self.to_roon.send(IoEvent::Control(Control::PlayPause)).await.unwrap(),
-                        KeyCode::Char('q') => self.to_roon.send(IoEvent::QueueModeNext).await.unwrap(),
-                        KeyCode::Char('a') => self.to_roon.send(IoEvent::QueueModeAppend).await.unwrap(),
-                        KeyCode::Char('z') => {
-                            if selected_view != Some(View::Zones) {
-                                match selected_view {
-                                    Some(View::Prompt) => self.restore_view(),
-                                    Some(View::Grouping) => self.restore_view(),
-                                    Some(View::Help) => self.restore_view(),
-                                    _ => (),
-                                }
+            // Global key codes, the keymap leaves plain characters to the views
+            if let Some(action) = self.keymap.get_action(&key) {
+                return self.do_global_action(action, selected_view).await;
+            }
 
-                                self.select_view(Some(View::Zones));
-                            }
+            if key.modifiers == KeyModifiers::NONE {
+                // Key codes specific to the active view
+                if let Some(view) = selected_view.as_ref() {
+                    let result = match *view {
+                        View::NowPlaying => self.handle_now_playing_key_codes(key).await,
+                        View::Queue => self.handle_queue_key_codes(key).await,
+                        View::Bookmarks => self.handle_bookmark_key_codes(key).await,
+                        View::Confirm => self.handle_confirm_key_codes(key).await,
+                        View::ZoneSettings => self.handle_zone_settings_key_codes(key).await,
+                        View::Overview => self.handle_overview_key_codes(key).await,
+                        View::Grouping => {
+                            self.handle_grouping_key_codes(key).await.unwrap_or(AppReturn::Continue)
                         }
-                        KeyCode::Char('g') => {
-                            if selected_view != Some(View::Grouping) {
-                                self.to_roon.send(IoEvent::ZoneGroupReq).await.unwrap();
-                            }
+                        View::Help => {
+                            self.restore_view();
+                            AppReturn::Continue
                         }
-                        KeyCode::Char('h') => {
-                            if selected_view != Some(View::Help) {
-                                match selected_view {
-                                    Some(View::Prompt) => self.restore_view(),
-                                    Some(View::Zones) => self.restore_view(),
-                                    Some(View::Grouping) => self.restore_view(),
-                                    _ => (),
-                                }
+                        _ => AppReturn::Continue,
+                    };
 
-                                self.select_view(Some(View::Help));
-                            }
-                        }
-                        KeyCode::Char('c') => return AppReturn::Exit,
-                        _ => (),
+                    if result == AppReturn::Exit {
+                        return result;
                     }
                 }
-                _ => (),
             }
 
             // Key codes specific to the active view (with own modifier handling)
             if let Some(view) = selected_view.as_ref() {
                 match *view {
-                    View::Browse => self.handle_browse_key_codes(key).await,
-                    View::Prompt => self.handle_prompt_key_codes(key).await,
+                    View::Browse => return self.handle_browse_key_codes(key).await,
+                    View::Zones => return self.handle_zone_key_codes(key).await,
+                    View::Prompt => return self.handle_prompt_key_codes(key).await,
                     View::GroupingPreset => self.handle_preset_key_codes(key).await,
                     _ => (),
                 }
@@ -618,81 +1025,772 @@ impl App {
         AppReturn::Continue
     }
 
-    async fn handle_browse_key_codes(&mut self, key: KeyEvent) {
-        match key.modifiers {
-            KeyModifiers::CONTROL => {
-                if key.code == KeyCode::Home {
-                    self.to_roon.send(IoEvent::BrowseHome).await.unwrap();
-                }
+    async fn do_global_action(&mut self, action: Action, selected_view: Option<View>) -> AppReturn {
+        match action {
+            Action::NextView => {
+                self.full_screen = false;
+                self.input.clear();
+                self.browse_match_list.clear();
+                self.select_next_view();
             }
-            KeyModifiers::SHIFT => {
-                match key.code {
-                    KeyCode::Char(key) => self.select_by_input(key),
-                    _ => (),
+            Action::PrevView => {
+                self.full_screen = false;
+                self.input.clear();
+                self.browse_match_list.clear();
+                self.select_prev_view();
+            }
+            Action::VolumeUp => return self.send_to_roon(IoEvent::ChangeVolume(self.volume_step)).await,
+            Action::VolumeDown => return self.send_to_roon(IoEvent::ChangeVolume(-self.volume_step)).await,
+            Action::VolumeUpCoarse => {
+                return self.send_to_roon(IoEvent::ChangeVolume(self.volume_step * VOLUME_COARSE_FACTOR)).await;
+            }
+            Action::VolumeDownCoarse => {
+                return self.send_to_roon(IoEvent::ChangeVolume(-self.volume_step * VOLUME_COARSE_FACTOR)).await;
+            }
+            Action::PrevTrack => return self.send_to_roon(IoEvent::Control(Control::Previous)).await,
+            Action::NextTrack => return self.send_to_roon(IoEvent::Control(Control::Next)).await,
+            Action::QueueClear => return self.confirm_action("Clear queue?", IoEvent::QueueClear, selected_view).await,
+            Action::Reconnect => return self.send_to_roon(IoEvent::Reconnect).await,
+            Action::QueueToggle => {
+                self.queue_hidden = !self.queue_hidden;
+
+                if self.queue_hidden && selected_view == Some(View::Queue) {
+                    self.select_view(Some(View::Browse));
                 }
             }
-            KeyModifiers::NONE => {
-                match key.code {
-                    KeyCode::Char(key) => self.select_by_input(key),
-                    KeyCode::Backspace => {
-                        self.input.pop();
-                        self.browse_match_list.pop();
-                        self.browse.select(self.browse_match_list.last().cloned());
-                    }
-                    KeyCode::Up => self.browse.prev(),
-                    KeyCode::Down => self.browse.next(),
-                    KeyCode::Enter => {
-                        self.input.clear();
-                        self.browse_match_list.clear();
-                        let item_key = self.get_item_key();
+            Action::CompactToggle => {
+                self.compact = !self.compact;
+                self.browse.refresh_paging();
+                self.queue.refresh_paging();
+            }
+            Action::Search => {
+                let is_text_input = matches!(selected_view, Some(View::Prompt) | Some(View::GroupingPreset));
 
-                        if let Some(item) = self.browse.get_selected_item() {
-                            if let Some(prompt) = item.input_prompt.as_ref() {
-                                self.prompt = prompt.prompt.to_owned();
-                                self.pending_item_key = item_key;
-                                self.select_view(Some(View::Prompt));
-                            } else {
-                                self.to_roon.send(IoEvent::BrowseSelected(item_key)).await.unwrap();
-                            }
-                        }
-                    }
-                    KeyCode::Esc => {
-                        self.input.clear();
-                        self.browse_match_list.clear();
-                        self.to_roon.send(IoEvent::BrowseBack).await.unwrap();
-                    }
-                    KeyCode::Home => {
-                        if self.input.is_empty() {
-                            self.browse.select_first();
-                        } else {
-                            self.input.clear();
-                            self.browse_match_list.clear();
-                        }
-                    }
-                    KeyCode::End => self.browse.select_last(),
-                    KeyCode::PageUp => self.browse.select_prev_page(),
-                    KeyCode::PageDown => self.browse.select_next_page(),
-                    KeyCode::F(5) => self.to_roon.send(IoEvent::BrowseRefresh).await.unwrap(),
-                    _ => (),
+                if !is_text_input {
+                    return self.send_to_roon(IoEvent::BrowseSearch).await;
                 }
             }
-            _ => (),
-        }
-    }
+            Action::SplitLeft | Action::SplitRight => {
+                let is_text_input = matches!(selected_view, Some(View::Prompt) | Some(View::GroupingPreset));
 
-    async fn handle_now_playing_key_codes(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('m') => self.to_roon.send(IoEvent::Mute(volume::Mute::Mute)).await.unwrap(),
-            KeyCode::Char('u') => self.to_roon.send(IoEvent::Mute(volume::Mute::Unmute)).await.unwrap(),
-            KeyCode::Char('+') => self.to_roon.send(IoEvent::ChangeVolume(1)).await.unwrap(),
-            KeyCode::Char('-') => self.to_roon.send(IoEvent::ChangeVolume(-1)).await.unwrap(),
-            KeyCode::Char('r') => self.to_roon.send(IoEvent::Repeat).await.unwrap(),
-            KeyCode::Char('s') => self.to_roon.send(IoEvent::Shuffle).await.unwrap(),
-            _ => (),
-        }
-    }
+                if !is_text_input {
+                    self.split = if action == Action::SplitLeft {
+                        self.split.saturating_sub(SPLIT_STEP).max(SPLIT_MIN)
+                    } else {
+                        (self.split + SPLIT_STEP).min(SPLIT_MAX)
+                    };
 
-    async fn handle_queue_key_codes(&mut self, key: KeyEvent) {
+                    return self.send_to_roon(IoEvent::SplitChanged(self.split)).await;
+                }
+            }
+            Action::PauseOnTrackEnd => return self.send_to_roon(IoEvent::PauseOnTrackEndReq).await,
+            Action::PlayPause => return self.send_to_roon(IoEvent::Control(Control::PlayPause)).await,
+            Action::QueueMode => return self.send_to_roon(IoEvent::QueueModeNext).await,
+            Action::QueueAppend => return self.send_to_roon(IoEvent::QueueModeAppend).await,
+            Action::ZoneSelect => {
+                if selected_view != Some(View::Zones) {
+                    match selected_view {
+                        Some(View::Prompt) => self.restore_view(),
+                        Some(View::Grouping) => self.restore_view(),
+                        Some(View::Help) => self.restore_view(),
+                        Some(View::Bookmarks) => self.restore_view(),
+                        Some(View::ZoneSettings) => self.restore_view(),
+                        Some(View::Overview) => self.restore_view(),
+                        Some(View::Confirm) => self.restore_view(),
+                        _ => (),
+                    }
+
+                    self.select_view(Some(View::Zones));
+                }
+            }
+            Action::ZoneGroup => {
+                if selected_view != Some(View::Grouping) {
+                    return self.send_to_roon(IoEvent::ZoneGroupReq).await;
+                }
+            }
+            Action::ZonesOverview => {
+                if selected_view != Some(View::Overview) {
+                    match selected_view {
+                        Some(View::Prompt) => self.restore_view(),
+                        Some(View::Zones) => self.restore_view(),
+                        Some(View::Grouping) => self.restore_view(),
+                        Some(View::Help) => self.restore_view(),
+                        Some(View::Bookmarks) => self.restore_view(),
+                        Some(View::ZoneSettings) => self.restore_view(),
+                        Some(View::Confirm) => self.restore_view(),
+                        _ => (),
+                    }
+
+                    self.select_view(Some(View::Overview));
+
+                    return self.send_to_roon(IoEvent::ZonesOverviewReq).await;
+                }
+            }
+            Action::Help => {
+                if selected_view != Some(View::Help) {
+                    match selected_view {
+                        Some(View::Prompt) => self.restore_view(),
+                        Some(View::Zones) => self.restore_view(),
+                        Some(View::Grouping) => self.restore_view(),
+                        Some(View::Bookmarks) => self.restore_view(),
+                        Some(View::ZoneSettings) => self.restore_view(),
+                        Some(View::Overview) => self.restore_view(),
+                        Some(View::Confirm) => self.restore_view(),
+                        _ => (),
+                    }
+
+                    self.select_view(Some(View::Help));
+                }
+            }
+            Action::Quit => return AppReturn::Exit,
+        }
+
+        AppReturn::Continue
+    }
+
+    async fn confirm_action(&mut self, question: &'static str, io_event: IoEvent, selected_view: Option<View>) -> AppReturn {
+        // Nothing to confirm when the request isn't sent anyway
+        if self.no_confirm || (self.monitor && io_event.is_control()) {
+            return self.send_to_roon(io_event).await;
+        }
+
+        match selected_view {
+            Some(View::Prompt) => self.restore_view(),
+            Some(View::Zones) => self.restore_view(),
+            Some(View::Grouping) => self.restore_view(),
+            Some(View::Help) => self.restore_view(),
+            Some(View::Bookmarks) => self.restore_view(),
+            Some(View::ZoneSettings) => self.restore_view(),
+            Some(View::Overview) => self.restore_view(),
+            Some(View::Confirm) => return AppReturn::Continue,
+            _ => (),
+        }
+
+        self.select_view(Some(View::Confirm));
+        self.confirm = Some((question, io_event));
+
+        AppReturn::Continue
+    }
+
+    // Returns the boolean settings of the selected zone, as can be toggled
+    fn get_zone_settings(&self) -> Option<Vec<(String, bool)>> {
+        let settings = serde_json::to_value(&self.selected_zone.as_ref()?.settings).ok()?;
+        let zone_settings = settings.as_object()?
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_owned(), value.as_bool()?)))
+            .collect();
+
+        Some(zone_settings)
+    }
+
+    async fn handle_zone_settings_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        match key.code {
+            KeyCode::Up => self.zone_settings.prev(),
+            KeyCode::Down => self.zone_settings.next(),
+            KeyCode::Home => self.zone_settings.select_first(),
+            KeyCode::End => self.zone_settings.select_last(),
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                if let Some((name, _)) = self.zone_settings.get_selected_item() {
+                    return self.send_to_roon(IoEvent::ZoneSettingToggle(name.to_owned())).await;
+                }
+            }
+            KeyCode::Esc => self.restore_view(),
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    // The overview entry of the selected zone
+    fn get_overview_index(&self) -> Option<usize> {
+        let zone_id = &self.selected_zone.as_ref()?.zone_id;
+
+        self.zones_overview.items.as_ref()?
+            .iter()
+            .position(|summary| summary.zone_id == *zone_id)
+    }
+
+    async fn handle_overview_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        match key.code {
+            KeyCode::Up => self.zones_overview.prev(),
+            KeyCode::Down => self.zones_overview.next(),
+            KeyCode::Home => self.zones_overview.select_first(),
+            KeyCode::End => self.zones_overview.select_last(),
+            KeyCode::PageUp => self.zones_overview.select_prev_page(),
+            KeyCode::PageDown => self.zones_overview.select_next_page(),
+            KeyCode::Enter => {
+                if let Some(summary) = self.zones_overview.get_selected_item() {
+                    let end_point = EndPoint::Zone(summary.zone_id.to_owned());
+
+                    self.restore_view();
+
+                    return self.send_to_roon(IoEvent::ZoneSelected(end_point)).await;
+                }
+            }
+            KeyCode::Esc => self.restore_view(),
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    async fn handle_confirm_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        match key.code {
+            KeyCode::Char('y') => {
+                let confirm = self.confirm.take();
+
+                self.restore_view();
+
+                if let Some((_, io_event)) = confirm {
+                    return self.send_to_roon(io_event).await;
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.restore_view(),
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    async fn handle_mouse_event(&mut self, mouse: MouseEvent) -> AppReturn {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => return self.handle_mouse_click(mouse).await,
+            MouseEventKind::ScrollUp => self.scroll_list_at(mouse.column, mouse.row, true),
+            MouseEventKind::ScrollDown => {
+                self.scroll_list_at(mouse.column, mouse.row, false);
+
+                if self.queue.contains(mouse.column, mouse.row) {
+                    return self.load_more_queue_items().await;
+                }
+            }
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    fn scroll_list_at(&mut self, column: u16, row: u16, up: bool) {
+        // Popups cover the underlying views
+        match self.selected_view {
+            Some(View::Zones) => {
+                if self.zones.contains(column, row) {
+                    self.zones.scroll(up);
+                }
+            }
+            Some(View::Grouping) => {
+                if self.grouping.contains(column, row) {
+                    self.grouping.scroll(up);
+                }
+            }
+            Some(View::Bookmarks) => {
+                if self.bookmarks.contains(column, row) {
+                    self.bookmarks.scroll(up);
+                }
+            }
+            Some(View::ZoneSettings) => {
+                if self.zone_settings.contains(column, row) {
+                    self.zone_settings.scroll(up);
+                }
+            }
+            Some(View::Overview) if self.zones_overview.contains(column, row) => self.zones_overview.scroll(up),
+            Some(View::Browse) | Some(View::Queue) | Some(View::NowPlaying) => {
+                if self.browse.contains(column, row) {
+                    self.browse.scroll(up);
+                } else if self.queue.contains(column, row) {
+                    self.queue.scroll(up);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    async fn handle_mouse_click(&mut self, mouse: MouseEvent) -> AppReturn {
+        // Ignore clicks while a popup is shown
+        match self.selected_view {
+            Some(View::Browse) | Some(View::Queue) | Some(View::NowPlaying) => (),
+            _ => return AppReturn::Continue,
+        }
+
+        let now = Instant::now();
+        let is_double_click = match self.last_click {
+            Some((instant, row)) => row == mouse.row && now.duration_since(instant) < DOUBLE_CLICK_INTERVAL,
+            None => false,
+        };
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        // A third click starts a new double click
+        self.last_click = if is_double_click {None} else {Some((now, mouse.row))};
+
+        if let Some(index) = self.browse.get_index_at(mouse.column, mouse.row) {
+            if self.selected_view != Some(View::Browse) {
+                self.input.clear();
+                self.browse_match_list.clear();
+                self.select_view(Some(View::Browse));
+            }
+
+            self.browse.state.select(Some(index));
+
+            if is_double_click {
+                return self.handle_browse_key_codes(enter).await;
+            }
+        } else if let Some(index) = self.queue.get_index_at(mouse.column, mouse.row) {
+            if self.selected_view != Some(View::Queue) {
+                self.input.clear();
+                self.browse_match_list.clear();
+                self.select_view(Some(View::Queue));
+            }
+
+            self.queue.state.select(Some(index));
+
+            if is_double_click {
+                return self.handle_queue_key_codes(enter).await;
+            }
+        } else if let Some(seconds) = self.get_seek_seconds_at(mouse.column, mouse.row) {
+            return self.send_to_roon(IoEvent::Seek(Seek::Absolute, seconds)).await;
+        }
+
+        AppReturn::Continue
+    }
+
+    fn copy_now_playing(&mut self) {
+        let now_playing = match self.selected_zone.as_ref().and_then(|zone| zone.now_playing.as_ref()) {
+            Some(now_playing) => now_playing,
+            None => {
+                self.message = Some("Nothing playing to copy");
+                return;
+            }
+        };
+        let three_line = &now_playing.three_line;
+        let text = [&three_line.line2, &three_line.line1, &three_line.line3]
+            .into_iter()
+            .filter(|line| !line.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" - ");
+
+        self.message = if self.clipboard.set_text(text) {
+            Some("Copied to clipboard")
+        } else {
+            Some("Clipboard not available")
+        };
+    }
+
+    fn copy_browse_item(&mut self) {
+        let item = match self.browse.get_selected_item() {
+            Some(item) => item,
+            None => {
+                self.message = Some("No item selected to copy");
+                return;
+            }
+        };
+        let text = match item.subtitle.as_deref().filter(|subtitle| !subtitle.is_empty()) {
+            Some(subtitle) => format!("{} - {}", item.title, subtitle),
+            None => item.title.to_owned(),
+        };
+
+        self.message = if self.clipboard.set_text(text) {
+            Some("Copied to clipboard")
+        } else {
+            Some("Clipboard not available")
+        };
+    }
+
+    fn export_queue(&mut self) {
+        let file_name = self.input.trim();
+
+        if file_name.is_empty() {
+            return;
+        }
+
+        let path = self.export_dir.join(file_name);
+        let items = self.queue.items.as_deref().unwrap_or_default();
+
+        self.message = match export::write_queue(&path, items) {
+            Ok(()) => Some("Queue exported"),
+            Err(err) => {
+                log::warn!("Failed to export the queue to {}: {}", path.display(), err);
+                Some("Failed to export the queue")
+            }
+        };
+    }
+
+    fn prompt_volume(&mut self) -> Option<()> {
+        let zone = self.selected_zone.as_ref()?;
+
+        // Absolute volume requires a volume range, which incremental outputs don't have
+        let volume = zone.outputs.iter()
+            .filter_map(|output| output.volume.as_ref())
+            .find(|volume| matches!(volume.scale, volume::Scale::Decibel | volume::Scale::Number))?;
+        let min = volume.min?;
+        let max = volume.max?;
+
+        self.prompt = format!("Volume ({} to {})", min, max);
+        self.input = volume.value.map(|value| value.to_string()).unwrap_or_default();
+        self.move_cursor_end();
+        self.volume_range = Some((min, max));
+        self.select_view(Some(View::Prompt));
+
+        Some(())
+    }
+
+    async fn seek_relative(&mut self, seconds: i64) -> AppReturn {
+        match self.get_seek_target(seconds) {
+            Some(target) => {
+                self.last_seek = Some((Instant::now(), target));
+
+                self.send_to_roon(IoEvent::Seek(Seek::Absolute, target as i32)).await
+            }
+            None => AppReturn::Continue,
+        }
+    }
+
+    fn get_seek_target(&self, seconds: i64) -> Option<i64> {
+        let zone = self.selected_zone.as_ref()?;
+        let now_playing = zone.now_playing.as_ref()?;
+        let length = now_playing.length? as i64;
+
+        if !zone.is_seek_allowed {
+            return None;
+        }
+
+        // The reported seek position lags behind, continue from the previous target while the key is held
+        let position = match self.last_seek {
+            Some((instant, target)) if instant.elapsed() < SEEK_SETTLE_TIME => target,
+            _ => self.get_seek_position()? as i64,
+        };
+
+        Some((position + seconds).clamp(0, length))
+    }
+
+    fn get_seek_position(&self) -> Option<f64> {
+        let zone = self.selected_zone.as_ref()?;
+        let now_playing = zone.now_playing.as_ref()?;
+        let zone_seek_position = self.zone_seek.as_ref()
+            .filter(|zone_seek| zone_seek.zone_id == zone.zone_id)
+            .and_then(|zone_seek| zone_seek.seek_position);
+
+        match zone_seek_position {
+            Some(seek_position) => {
+                // Interpolate between the seek updates of the server, freeze when not playing
+                let elapsed = if zone.state == State::Playing {
+                    self.zone_seek_received.elapsed().as_secs_f64()
+                } else {
+                    0.0
+                };
+                let position = seek_position as f64 + elapsed;
+
+                match now_playing.length {
+                    Some(length) if length > 0 => Some(position.min(length as f64)),
+                    _ => Some(position),
+                }
+            }
+            None => now_playing.seek_position.map(|seek_position| seek_position as f64),
+        }
+    }
+
+    fn get_seek_seconds_at(&self, column: u16, row: u16) -> Option<i32> {
+        let area = self.gauge_area;
+
+        if row != area.y || column < area.x || column >= area.x + area.width {
+            return None;
+        }
+
+        let zone = self.selected_zone.as_ref()?;
+        let length = zone.now_playing.as_ref()?.length?;
+
+        if !zone.is_seek_allowed {
+            return None;
+        }
+
+        let position = (column - area.x) as u32;
+
+        Some((position * length / area.width as u32) as i32)
+    }
+
+    // Keeps the list when going a level deeper, to return to its selection when going back
+    fn update_browse_cache(&mut self, level: usize) {
+        let current = self.browse_breadcrumb.len();
+
+        if level > current {
+            let items = self.browse_unfiltered.as_ref().or(self.browse.items.as_ref());
+
+            if let Some(items) = items.cloned() {
+                let selected = self.browse.state.selected();
+                let selected = if self.browse_filter.is_some() {
+                    selected.and_then(|selected| self.browse_match_list.get(selected).cloned())
+                } else {
+                    selected
+                };
+
+                self.browse_cache.retain(|(cached, _, _)| *cached < current);
+                self.browse_cache.push((current, items, selected));
+
+                if self.browse_cache.len() > BROWSE_CACHE_LEVELS {
+                    self.browse_cache.remove(0);
+                }
+            }
+        } else if level < current {
+            let position = self.browse_cache.iter().position(|(cached, _, _)| *cached == level);
+
+            if let Some(position) = position {
+                let (_, items, selected) = self.browse_cache.swap_remove(position);
+
+                // Show the cached items until the reloaded ones arrive
+                self.clear_browse_filter();
+                self.browse.items = Some(items);
+                self.browse_restore = selected;
+
+                if self.selected_view == Some(View::Browse) {
+                    self.browse.select(selected);
+                }
+            }
+
+            self.browse_cache.retain(|(cached, _, _)| *cached < level);
+        }
+    }
+
+    fn start_browse_filter(&mut self) {
+        self.input.clear();
+        self.browse_match_list.clear();
+        self.browse_filter = Some(String::new());
+        self.browse_unfiltered = self.browse.items.clone();
+        self.apply_browse_filter();
+    }
+
+    fn apply_browse_filter(&mut self) {
+        if let (Some(filter), Some(items)) = (self.browse_filter.as_ref(), self.browse_unfiltered.as_ref()) {
+            let filter = any_ascii(filter).to_ascii_lowercase();
+
+            self.browse_match_list = items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| any_ascii(&item.title).to_ascii_lowercase().contains(filter.as_str()))
+                .map(|(index, _)| index)
+                .collect();
+            self.browse.items = Some(self.browse_match_list.iter().map(|index| items[*index].clone()).collect());
+
+            if self.browse_match_list.is_empty() {
+                self.browse.deselect();
+            } else {
+                self.browse.select_first();
+            }
+        }
+    }
+
+    fn clear_browse_filter(&mut self) {
+        if self.browse_filter.take().is_some() {
+            // Keep the selected item selected in the complete list
+            let index = self.browse.state.selected()
+                .and_then(|selected| self.browse_match_list.get(selected).cloned());
+
+            self.browse.items = self.browse_unfiltered.take();
+            self.browse_match_list.clear();
+            self.browse.select(index);
+        }
+    }
+
+    // Returns true when the key is consumed by the filter
+    fn filter_browse_by_key(&mut self, key: KeyEvent) -> bool {
+        let filter = match self.browse_filter.as_mut() {
+            Some(filter) => filter,
+            None => return false,
+        };
+
+        match key.code {
+            KeyCode::Char('/') | KeyCode::Esc => self.clear_browse_filter(),
+            KeyCode::Char(key) => {
+                filter.push(key);
+                self.apply_browse_filter();
+            }
+            KeyCode::Backspace => {
+                filter.pop();
+                self.apply_browse_filter();
+            }
+            KeyCode::Home | KeyCode::End | KeyCode::PageUp | KeyCode::PageDown | KeyCode::Enter => {
+                // Nothing to navigate or select without matches
+                return self.browse_match_list.is_empty();
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    async fn play_selected_item(&mut self, queue_action: QueueAction) -> AppReturn {
+        let item = match self.browse.get_selected_item() {
+            Some(item) if item.item_key.is_some() => item.to_owned(),
+            _ => return AppReturn::Continue,
+        };
+
+        self.input.clear();
+        self.browse_match_list.clear();
+
+        self.send_to_roon(IoEvent::BrowsePlayAction(item, queue_action)).await
+    }
+
+    async fn handle_browse_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        let is_filter_key = key.modifiers == KeyModifiers::NONE || key.modifiers == KeyModifiers::SHIFT;
+
+        if is_filter_key && self.filter_browse_by_key(key) {
+            return AppReturn::Continue;
+        }
+
+        match key.modifiers {
+            KeyModifiers::CONTROL => {
+                match key.code {
+                    KeyCode::Home => return self.send_to_roon(IoEvent::BrowseHome).await,
+                    KeyCode::Char('d') => {
+                        if let Some(title) = self.browse.title.as_ref() {
+                            return self.send_to_roon(IoEvent::BookmarkAdd(title.to_owned())).await;
+                        }
+                    }
+                    KeyCode::Char('b') => self.select_view(Some(View::Bookmarks)),
+                    KeyCode::Char('y') => self.copy_browse_item(),
+                    _ => (),
+                }
+            }
+            KeyModifiers::SHIFT => {
+                match key.code {
+                    KeyCode::Char('/') => self.start_browse_filter(),
+                    KeyCode::Char('P') => return self.play_selected_item(QueueAction::PlayNow).await,
+                    KeyCode::Char('A') => return self.play_selected_item(QueueAction::AddNext).await,
+                    KeyCode::Char('Q') => return self.play_selected_item(QueueAction::Queue).await,
+                    KeyCode::Char(key) => self.select_by_input(key),
+                    _ => (),
+                }
+            }
+            KeyModifiers::NONE => {
+                match key.code {
+                    KeyCode::Char('/') => self.start_browse_filter(),
+                    KeyCode::Char(key) => self.select_by_input(key),
+                    KeyCode::Backspace => {
+                        self.input.pop();
+                        self.browse_match_list.pop();
+                        self.browse.select(self.browse_match_list.last().cloned());
+
+                        let (matches, _) = self.get_input_matches(&self.input);
+
+                        self.browse_input_matches = Some((self.input.clone(), matches));
+                    }
+                    KeyCode::Up => self.browse.prev(),
+                    KeyCode::Down => self.browse.next(),
+                    KeyCode::Enter => {
+                        self.input.clear();
+                        self.browse_match_list.clear();
+                        let item_key = self.get_item_key();
+
+                        if let Some(item) = self.browse.get_selected_item() {
+                            if self.monitor && item.hint == Some(browse::ItemHint::Action) {
+                                self.message = Some(MONITOR_MESSAGE);
+                            } else if let Some(prompt) = item.input_prompt.as_ref() {
+                                self.prompt = prompt.prompt.to_owned();
+                                self.pending_item_key = item_key;
+                                self.select_view(Some(View::Prompt));
+                            } else {
+                                return self.send_to_roon(IoEvent::BrowseSelected(item_key)).await;
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.input.clear();
+                        self.browse_match_list.clear();
+
+                        return self.send_to_roon(IoEvent::BrowseBack).await;
+                    }
+                    KeyCode::Home => {
+                        // Moving to the top also ends the typed input
+                        self.input.clear();
+                        self.browse_match_list.clear();
+                        self.browse.select_first();
+                    }
+                    KeyCode::End => self.browse.select_last(),
+                    KeyCode::PageUp => self.browse.select_prev_page(),
+                    KeyCode::PageDown => self.browse.select_next_page(),
+                    KeyCode::F(5) => return self.send_to_roon(IoEvent::BrowseRefresh).await,
+                    _ => (),
+                }
+            }
+            modifiers if modifiers.contains(KeyModifiers::ALT) => {
+                // Plain characters extend the input, so the vi-style keys are used with Alt
+                match key.code {
+                    KeyCode::Char('n') => self.select_input_match(true),
+                    KeyCode::Char('N') => self.select_input_match(false),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    async fn handle_now_playing_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        match key.code {
+            KeyCode::Char('m') => self.send_to_roon(IoEvent::Mute(volume::Mute::Mute)).await,
+            KeyCode::Char('u') => self.send_to_roon(IoEvent::Mute(volume::Mute::Unmute)).await,
+            KeyCode::Char('+') => self.send_to_roon(IoEvent::ChangeVolume(self.volume_step)).await,
+            KeyCode::Char('-') => self.send_to_roon(IoEvent::ChangeVolume(-self.volume_step)).await,
+            KeyCode::Char('r') => self.send_to_roon(IoEvent::Repeat).await,
+            KeyCode::Char('s') => self.send_to_roon(IoEvent::Shuffle).await,
+            KeyCode::Char('v') => {
+                self.prompt_volume();
+
+                AppReturn::Continue
+            }
+            KeyCode::Char('f') => {
+                self.full_screen = !self.full_screen;
+
+                AppReturn::Continue
+            }
+            KeyCode::Char('d') => self.send_to_roon(IoEvent::RemainingTimeToggle).await,
+            KeyCode::Char('z') => {
+                self.prompt = "Sleep timer in minutes, 0 cancels".to_owned();
+                self.sleep_timer_prompt = true;
+                self.select_view(Some(View::Prompt));
+
+                AppReturn::Continue
+            }
+            KeyCode::Char('a') => {
+                self.prompt = "Alarm time as HH:MM, empty cancels".to_owned();
+                self.input = self.alarm.to_owned().unwrap_or_default();
+                self.move_cursor_end();
+                self.alarm_prompt = true;
+                self.select_view(Some(View::Prompt));
+
+                AppReturn::Continue
+            }
+            KeyCode::Char('c') => {
+                self.copy_now_playing();
+
+                AppReturn::Continue
+            }
+            KeyCode::Char('o') => {
+                if self.selected_zone.is_some() {
+                    self.zone_settings.items = self.get_zone_settings();
+                    self.select_view(Some(View::ZoneSettings));
+                }
+
+                AppReturn::Continue
+            }
+            KeyCode::Char('t') => {
+                match self.selected_zone.as_ref().map(|zone| &zone.state) {
+                    Some(State::Stopped) => self.message = Some("Nothing playing to transfer"),
+                    Some(_) => {
+                        self.select_view(Some(View::Zones));
+                        self.zone_transfer = true;
+                    }
+                    None => (),
+                }
+
+                AppReturn::Continue
+            }
+            KeyCode::Left | KeyCode::Char(',') => self.seek_relative(-SEEK_STEP).await,
+            KeyCode::Right | KeyCode::Char('.') => self.seek_relative(SEEK_STEP).await,
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn handle_queue_key_codes(&mut self, key: KeyEvent) -> AppReturn {
         match key.code {
             KeyCode::Up => self.queue.prev(),
             KeyCode::Down => self.queue.next(),
@@ -706,14 +1804,47 @@ impl App {
                     // meaning that the selected one will get on top
                     self.queue.select_first();
 
-                    self.to_roon.send(IoEvent::QueueSelected(queue_item_id)).await.unwrap();
+                    return self.send_to_roon(IoEvent::QueueSelected(queue_item_id)).await;
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(index) = self.get_now_playing_queue_index() {
+                    self.queue.select(Some(index));
                 }
             }
+            KeyCode::Char('e') if self.queue.items.as_ref().map(Vec::len).unwrap_or_default() > 0 => {
+                self.prompt = format!("Export queue to file in {}", self.export_dir.display());
+                self.input = "queue.m3u".to_owned();
+                self.move_cursor_end();
+                self.queue_export = true;
+                self.select_view(Some(View::Prompt));
+            }
             _ => (),
         }
+
+        self.load_more_queue_items().await
+    }
+
+    async fn load_more_queue_items(&mut self) -> AppReturn {
+        let items = match self.queue.items.as_ref() {
+            Some(items) => items,
+            None => return AppReturn::Continue,
+        };
+        let position = self.queue.state.selected().unwrap_or(self.queue.state.offset());
+
+        // Request more items when getting near the end of the loaded part of the queue
+        if !self.queue_loading && position + QUEUE_LOAD_THRESHOLD >= items.len() {
+            let loaded = items.len();
+
+            self.set_loading(self.browse_loading, true);
+
+            return self.send_to_roon(IoEvent::QueueLoadMore(loaded)).await;
+        }
+
+        AppReturn::Continue
     }
 
-    async fn handle_prompt_key_codes(&mut self, key: KeyEvent) {
+    async fn handle_prompt_key_codes(&mut self, key: KeyEvent) -> AppReturn {
         match key.modifiers {
             KeyModifiers::SHIFT => {
                 match key.code {
@@ -724,14 +1855,88 @@ impl App {
             KeyModifiers::NONE => {
                 match key.code {
                     KeyCode::Enter => {
+                        let mut result = AppReturn::Continue;
+
+                        if let Some((min, max)) = self.volume_range {
+                            match self.input.trim().parse::<f32>() {
+                                Ok(value) if value >= min && value <= max => {
+                                    self.volume_range = None;
+                                    result = self.send_to_roon(IoEvent::SetVolumeAbsolute(value)).await;
+                                }
+                                _ => {
+                                    // Keep the prompt open to retry
+                                    self.prompt = format!("Invalid volume, enter {} to {}", min, max);
+                                    self.input.clear();
+                                    self.reset_cursor();
+
+                                    return AppReturn::Continue;
+                                }
+                            }
+                        }
+
+                        if self.sleep_timer_prompt {
+                            match self.input.trim().parse::<u32>() {
+                                Ok(minutes) => {
+                                    self.sleep_timer_prompt = false;
+                                    result = self.send_to_roon(IoEvent::SleepTimer(minutes)).await;
+                                }
+                                Err(_) => {
+                                    // Keep the prompt open to retry
+                                    self.prompt = "Invalid number of minutes, 0 cancels".to_owned();
+                                    self.input.clear();
+                                    self.reset_cursor();
+
+                                    return AppReturn::Continue;
+                                }
+                            }
+                        }
+
+                        if self.alarm_prompt {
+                            let input = self.input.trim();
+                            let alarm = if input.is_empty() {
+                                Ok(None)
+                            } else {
+                                NaiveTime::parse_from_str(input, ALARM_FORMAT)
+                                    .map(|time| Some(time.format(ALARM_FORMAT).to_string()))
+                            };
+
+                            match alarm {
+                                Ok(alarm) => {
+                                    self.alarm_prompt = false;
+                                    result = self.send_to_roon(IoEvent::Alarm(alarm)).await;
+                                }
+                                Err(_) => {
+                                    // Keep the prompt open to retry
+                                    self.prompt = "Invalid time, enter HH:MM, empty cancels".to_owned();
+                                    self.input.clear();
+                                    self.reset_cursor();
+
+                                    return AppReturn::Continue;
+                                }
+                            }
+                        }
+
+                        if self.queue_export {
+                            self.queue_export = false;
+                            self.export_queue();
+                        }
+
                         if self.pending_item_key.is_some() {
-                            self.to_roon.send(IoEvent::BrowseInput(self.input.clone())).await.unwrap();
-                            self.to_roon.send(IoEvent::BrowseSelected(self.pending_item_key.take())).await.unwrap();
+                            let item_key = self.pending_item_key.take();
+
+                            self.search_history.add(&self.input);
+                            result = self.send_to_roon(IoEvent::BrowseInput(self.input.clone())).await;
+
+                            if result == AppReturn::Continue {
+                                result = self.send_to_roon(IoEvent::BrowseSelected(item_key)).await;
+                            }
                         }
 
                         self.input.clear();
                         self.reset_cursor();
                         self.restore_view();
+
+                        return result;
                     }
                     KeyCode::Char(to_insert) => self.enter_char(to_insert),
                     KeyCode::Backspace => self.delete_char(),
@@ -743,8 +1948,26 @@ impl App {
                     KeyCode::Right => self.move_cursor_right(),
                     KeyCode::Home => self.move_cursor_home(),
                     KeyCode::End => self.move_cursor_end(),
+                    KeyCode::Up | KeyCode::Down if self.pending_item_key.is_some() => {
+                        let entry = if key.code == KeyCode::Up {
+                            self.search_history.prev()
+                        } else {
+                            self.search_history.next()
+                        };
+
+                        if let Some(entry) = entry {
+                            self.input.clear();
+                            self.reset_cursor();
+                            self.enter_str(&entry);
+                        }
+                    }
                     KeyCode::Esc => {
+                        self.search_history.reset();
                         self.pending_item_key = None;
+                        self.volume_range = None;
+                        self.queue_export = false;
+                        self.sleep_timer_prompt = false;
+                        self.alarm_prompt = false;
                         self.input.clear();
                         self.reset_cursor();
                         self.restore_view();
@@ -754,9 +1977,123 @@ impl App {
             }
             _ => (),
         }
+
+        AppReturn::Continue
+    }
+
+    fn apply_zone_filter(&mut self) {
+        if self.zone_filter.is_empty() {
+            self.clear_zone_filter();
+
+            return;
+        }
+
+        if self.zones_unfiltered.is_none() {
+            self.zones_unfiltered = self.zones.items.take();
+        }
+
+        if let Some(items) = self.zones_unfiltered.as_ref() {
+            let filter = self.zone_filter.to_lowercase();
+
+            self.zone_match_list = items
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, name, _, _, _))| name.to_lowercase().contains(filter.as_str()))
+                .map(|(index, _)| index)
+                .collect();
+            self.zones.items = Some(self.zone_match_list.iter().map(|index| items[*index].clone()).collect());
+
+            if self.zone_match_list.is_empty() {
+                self.zones.deselect();
+            } else {
+                self.zones.select_first();
+            }
+        }
+    }
+
+    fn clear_zone_filter(&mut self) {
+        self.zone_filter.clear();
+
+        if let Some(items) = self.zones_unfiltered.take() {
+            // Keep the selected zone selected in the complete list
+            let index = self.zones.state.selected()
+                .and_then(|selected| self.zone_match_list.get(selected).cloned());
+
+            self.zones.items = Some(items);
+            self.zone_match_list.clear();
+
+            if self.selected_view == Some(View::Zones) {
+                self.zones.select(index);
+            }
+        }
+    }
+
+    async fn transfer_zone(&mut self, zone_id: String) -> AppReturn {
+        let is_selected = self.selected_zone.as_ref()
+            .map(|zone| zone.zone_id == zone_id)
+            .unwrap_or_default();
+
+        if is_selected {
+            return AppReturn::Continue;
+        }
+
+        if self.send_to_roon(IoEvent::ZoneTransfer(zone_id.to_owned())).await == AppReturn::Exit {
+            return AppReturn::Exit;
+        }
+
+        // Follow the playback to the new zone
+        self.send_to_roon(IoEvent::ZoneSelected(EndPoint::Zone(zone_id))).await
+    }
+
+    fn expand_zone(&mut self, expand: bool) -> Option<()> {
+        let (end_point, _, _, outputs, _) = self.zones.get_selected_item()?;
+        let zone_id = match end_point {
+            EndPoint::Zone(zone_id) if !outputs.is_empty() => zone_id.to_owned(),
+            _ => return None,
+        };
+
+        if expand {
+            self.expanded_zones.insert(zone_id);
+        } else {
+            self.expanded_zones.remove(&zone_id);
+        }
+
+        // Refresh paging for the changed line count
+        self.zones.select(self.zones.state.selected());
+
+        Some(())
     }
 
-    async fn handle_zone_key_codes(&mut self, key: KeyEvent) {
+    async fn handle_zone_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        // Characters are filter input, other keys are used without modifiers only
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(key)) => {
+                self.zone_filter.push(key);
+                self.apply_zone_filter();
+
+                return AppReturn::Continue;
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('s')) => return self.send_to_roon(IoEvent::ZoneSortNext).await,
+            (KeyModifiers::CONTROL, KeyCode::Char('o')) => {
+                // Only outputs supporting standby carry their output id
+                if let Some((_, _, _, _, Some((output_id, _)))) = self.zones.get_selected_item() {
+                    return self.send_to_roon(IoEvent::ToggleStandby(output_id.to_owned())).await;
+                }
+
+                return AppReturn::Continue;
+            }
+            (KeyModifiers::NONE, _) => (),
+            _ => return AppReturn::Continue,
+        }
+
+        // Nothing to navigate or select without matches
+        if self.zone_match_list.is_empty() && self.zones_unfiltered.is_some() {
+            match key.code {
+                KeyCode::Backspace | KeyCode::Esc => (),
+                _ => return AppReturn::Continue,
+            }
+        }
+
         match key.code {
             KeyCode::Up => self.zones.prev(),
             KeyCode::Down => self.zones.next(),
@@ -764,26 +2101,94 @@ impl App {
             KeyCode::End => self.zones.select_last(),
             KeyCode::PageUp => self.zones.select_prev_page(),
             KeyCode::PageDown => self.zones.select_next_page(),
+            KeyCode::Right => {
+                self.expand_zone(true);
+            }
+            KeyCode::Left => {
+                self.expand_zone(false);
+            }
             KeyCode::Enter => {
-                if let Some((end_point, _)) = self.zones.get_selected_item() {
-                    self.to_roon.send(IoEvent::ZoneSelected(end_point.to_owned())).await.unwrap();
-                }
+                let end_point = self.zones.get_selected_item().map(|(end_point, _, _, _, _)| end_point.to_owned());
+                let zone_transfer = self.zone_transfer;
 
                 self.restore_view();
+
+                if zone_transfer {
+                    if let Some(EndPoint::Zone(zone_id)) = end_point {
+                        return self.transfer_zone(zone_id).await;
+                    }
+                } else if let Some(end_point) = end_point {
+                    return self.send_to_roon(IoEvent::ZoneSelected(end_point)).await;
+                }
             }
             KeyCode::Delete => {
-                if let Some((end_point, _)) = self.zones.get_selected_item() {
+                if let Some((end_point, _, _, _, _)) = self.zones.get_selected_item() {
                     if let EndPoint::Preset(preset) = end_point {
-                        self.to_roon.send(IoEvent::ZoneDeletePreset(preset.to_owned())).await.unwrap();
+                        return self.send_to_roon(IoEvent::ZoneDeletePreset(preset.to_owned())).await;
                     }
                 }
             }
+            KeyCode::F(2) => {
+                if let Some((EndPoint::Preset(preset), _, _, _, _)) = self.zones.get_selected_item() {
+                    let preset = preset.to_owned();
+
+                    self.edit_preset = Some(preset.to_owned());
+                    self.restore_view();
+
+                    return self.send_to_roon(IoEvent::ZoneEditPreset(preset)).await;
+                }
+            }
+            KeyCode::Backspace => {
+                self.zone_filter.pop();
+                self.apply_zone_filter();
+            }
+            KeyCode::Esc => {
+                if self.zone_filter.is_empty() {
+                    self.restore_view();
+                } else {
+                    self.clear_zone_filter();
+                }
+            }
+            _ => (),
+        }
+
+        AppReturn::Continue
+    }
+
+    async fn handle_bookmark_key_codes(&mut self, key: KeyEvent) -> AppReturn {
+        let is_empty = self.bookmarks.items.as_ref().map(Vec::len).unwrap_or_default() == 0;
+
+        match key.code {
             KeyCode::Esc => self.restore_view(),
+            // Nothing to navigate before the first bookmark is added
+            _ if is_empty => (),
+            KeyCode::Up => self.bookmarks.prev(),
+            KeyCode::Down => self.bookmarks.next(),
+            KeyCode::Home => self.bookmarks.select_first(),
+            KeyCode::End => self.bookmarks.select_last(),
+            KeyCode::PageUp => self.bookmarks.select_prev_page(),
+            KeyCode::PageDown => self.bookmarks.select_next_page(),
+            KeyCode::Enter => {
+                let name = self.bookmarks.get_selected_item().cloned();
+
+                self.restore_view();
+
+                if let Some(name) = name {
+                    return self.send_to_roon(IoEvent::BookmarkSelect(name)).await;
+                }
+            }
+            KeyCode::Delete => {
+                if let Some(name) = self.bookmarks.get_selected_item() {
+                    return self.send_to_roon(IoEvent::BookmarkDelete(name.to_owned())).await;
+                }
+            }
             _ => (),
         }
+
+        AppReturn::Continue
     }
 
-    async fn handle_grouping_key_codes(&mut self, key: KeyEvent) -> Option<()> {
+    async fn handle_grouping_key_codes(&mut self, key: KeyEvent) -> Option<AppReturn> {
         match key.code {
             KeyCode::Up => self.grouping.prev(),
             KeyCode::Down => self.grouping.next(),
@@ -800,7 +2205,8 @@ impl App {
 
                 if output_ids.len() > 1 {
                     self.draft_match = true;
-                    self.to_roon.send(IoEvent::ZoneMatchPreset(output_ids)).await.unwrap();
+
+                    return Some(self.send_to_roon(IoEvent::ZoneMatchPreset(output_ids)).await);
                 }
             }
             KeyCode::Enter => {
@@ -810,30 +2216,50 @@ impl App {
                 let output_ids = self.get_included_output_ids(items);
 
                 if !output_ids.is_empty() {
-                    if !self.input.is_empty() {
-                        if output_ids.len() > 1 {
-                            self.to_roon.send(IoEvent::ZoneSavePreset(self.input.to_owned(), output_ids)).await.unwrap();
-                        } else {
-                            self.to_roon.send(IoEvent::ZoneGrouped(output_ids)).await.unwrap();
-                        }
-
-                        self.input.clear();
-                        self.reset_cursor();
+                    let io_event = if !self.input.is_empty() && output_ids.len() > 1 {
+                        IoEvent::ZoneSavePreset(self.input.to_owned(), output_ids)
                     } else {
-                        self.to_roon.send(IoEvent::ZoneGrouped(output_ids)).await.unwrap();
-                    }
+                        IoEvent::ZoneGrouped(output_ids)
+                    };
+
+                    self.input.clear();
+                    self.reset_cursor();
+
+                    return Some(self.send_to_roon(io_event).await);
                 }
             }
             KeyCode::Char('s') => {
                 self.save_preset();
             }
+            KeyCode::Char('+') => {
+                let output_id = self.grouping.get_selected_item()?.0.to_owned();
+
+                return Some(self.send_to_roon(IoEvent::ChangeOutputVolume(output_id, self.volume_step)).await);
+            }
+            KeyCode::Char('-') => {
+                let output_id = self.grouping.get_selected_item()?.0.to_owned();
+
+                return Some(self.send_to_roon(IoEvent::ChangeOutputVolume(output_id, -self.volume_step)).await);
+            }
+            KeyCode::Char('m') => {
+                let output_id = self.grouping.get_selected_item()?.0.to_owned();
+
+                return Some(self.send_to_roon(IoEvent::MuteOutput(output_id, volume::Mute::Mute)).await);
+            }
+            KeyCode::Char('u') => {
+                let output_id = self.grouping.get_selected_item()?.0.to_owned();
+
+                return Some(self.send_to_roon(IoEvent::MuteOutput(output_id, volume::Mute::Unmute)).await);
+            }
             KeyCode::Esc => {
+                self.input.clear();
+                self.reset_cursor();
                 self.restore_view();
             }
             _ => (),
         }
 
-        Some(())
+        Some(AppReturn::Continue)
     }
 
     async fn handle_preset_key_codes(&mut self, key: KeyEvent) {
@@ -881,7 +2307,7 @@ impl App {
         if output_ids.len() > 1 {
             if let Some(preset)  = self.matched_draft_preset.as_deref() {
                 self.input = preset.to_owned();
-                self.cursor_position = self.input.len();
+                self.move_cursor_end();
             }
 
             self.selected_view = Some(View::GroupingPreset);
@@ -899,6 +2325,84 @@ impl App {
         Some(self.queue.get_selected_item()?.queue_item_id)
     }
 
+    fn set_loading(&mut self, browse_loading: bool, queue_loading: bool) {
+        if !self.browse_loading && !self.queue_loading {
+            self.spinner_start = Instant::now();
+        }
+
+        self.browse_loading = browse_loading;
+        self.queue_loading = queue_loading;
+        self.update_ticking();
+    }
+
+    // Errors are shown longer, to have time to read why something failed
+    fn set_status(&mut self, status: String, is_error: bool) {
+        let duration = if is_error {ERROR_STATUS_DURATION} else {STATUS_DURATION};
+
+        self.status = Some((status, is_error, Instant::now() + duration));
+        self.update_ticking();
+    }
+
+    fn update_ticking(&self) {
+        let playing = match self.selected_zone.as_ref() {
+            Some(zone) => zone.state == State::Playing,
+            None => false,
+        };
+
+        // Ticks are used to animate the playing track and the loading spinner, and to clear the status
+        let ticking = playing || self.browse_loading || self.queue_loading || self.status.is_some();
+
+        self.ticking.store(ticking, Ordering::Relaxed);
+    }
+
+    fn update_queue_duration(&mut self) {
+        self.queue_duration = match self.queue.items.as_ref() {
+            Some(items) => items.iter().map(|item| item.length).sum(),
+            None => 0,
+        };
+    }
+
+    fn save_queue_position(&mut self) {
+        if let Some(zone) = self.selected_zone.as_ref() {
+            if let Some(index) = self.queue.state.selected() {
+                self.queue_positions.insert(zone.zone_id.to_owned(), index);
+            }
+        }
+    }
+
+    fn get_saved_queue_position(&self) -> Option<usize> {
+        let zone_id = &self.selected_zone.as_ref()?.zone_id;
+        let index = *self.queue_positions.get(zone_id)?;
+        let item_count = self.queue.items.as_ref()?.len();
+
+        // The queue might have shrunk in the meantime
+        if item_count > 0 {
+            Some(index.min(item_count - 1))
+        } else {
+            None
+        }
+    }
+
+    fn restore_queue_position(&mut self) {
+        let index = self.get_saved_queue_position();
+
+        if self.queue.is_selected() {
+            self.queue.select(index);
+        } else {
+            // Only scroll when the Queue view isn't active
+            *self.queue.state.offset_mut() = index.unwrap_or(0);
+        }
+    }
+
+    fn get_now_playing_queue_index(&self) -> Option<usize> {
+        // The queue starts at the playing track, the ones played are removed from it
+        self.selected_zone.as_ref()?.now_playing.as_ref()?;
+
+        let is_empty = self.queue.items.as_ref()?.is_empty();
+
+        (!is_empty).then_some(0)
+    }
+
     fn get_included_output_ids(&self, items: &Vec<(String, String, bool)>) -> Vec<String> {
         items.iter()
             .filter_map(|(output_id, _, included)| {
@@ -911,3 +2415,131 @@ impl App {
             .collect()
     }
 }
+
+// Applies the changes in order to the loaded part of the queue,
+// returns true when changes beyond the loaded part were skipped
+fn apply_queue_changes(queue: &mut Vec<QueueItem>, changes: &[QueueChange]) -> Option<bool> {
+    let mut skipped = false;
+
+    for change in changes {
+        match change.operation {
+            QueueOperation::Insert => {
+                for i in 0..change.items.as_ref()?.len() {
+                    let item = change.items.as_ref()?.get(i)?;
+
+                    // Skip items beyond the loaded part of a long queue
+                    if change.index + i <= queue.len() {
+                        queue.insert(change.index + i, item.to_owned());
+                    } else {
+                        skipped = true;
+                    }
+                }
+            }
+            QueueOperation::Remove => {
+                for _ in 0..change.count? {
+                    if change.index < queue.len() {
+                        queue.remove(change.index);
+                    } else {
+                        skipped = true;
+                    }
+                }
+            }
+        }
+    }
+
+    Some(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use roon_api::transport::{OneLine, ThreeLine, TwoLine};
+
+    use super::*;
+
+    fn create_item(queue_item_id: u32, title: &str) -> QueueItem {
+        QueueItem {
+            image_key: None,
+            length: 180,
+            queue_item_id,
+            one_line: OneLine { line1: title.to_owned() },
+            two_line: TwoLine { line1: title.to_owned(), line2: "Artist".to_owned() },
+            three_line: ThreeLine { line1: title.to_owned(), line2: "Artist".to_owned(), line3: "Album".to_owned() },
+        }
+    }
+
+    fn get_ids(queue: &[QueueItem]) -> Vec<u32> {
+        queue.iter().map(|item| item.queue_item_id).collect()
+    }
+
+    #[test]
+    fn selection_follows_the_queue_item_id() {
+        // The same track is queued three times
+        let mut queue = vec![
+            create_item(1, "Song"),
+            create_item(2, "Other"),
+            create_item(3, "Song"),
+            create_item(4, "Song"),
+        ];
+        let selected = queue[2].queue_item_id;
+        let changes = [
+            QueueChange {
+                operation: QueueOperation::Remove,
+                index: 0,
+                items: None,
+                count: Some(2),
+            },
+            QueueChange {
+                operation: QueueOperation::Insert,
+                index: 1,
+                items: Some(vec![create_item(5, "Song"), create_item(6, "Song")]),
+                count: None,
+            },
+        ];
+
+        assert_eq!(apply_queue_changes(&mut queue, &changes), Some(false));
+        assert_eq!(get_ids(&queue), [3, 5, 6, 4]);
+
+        let index = queue.iter().position(|item| item.queue_item_id == selected);
+
+        // A match on the title would select the first "Song" that remains
+        assert_eq!(index, Some(0));
+
+        let changes = [
+            QueueChange {
+                operation: QueueOperation::Insert,
+                index: 0,
+                items: Some(vec![create_item(7, "Song")]),
+                count: None,
+            },
+        ];
+
+        assert_eq!(apply_queue_changes(&mut queue, &changes), Some(false));
+
+        let index = queue.iter().position(|item| item.queue_item_id == selected);
+
+        assert_eq!(index, Some(1));
+        assert_eq!(queue[1].two_line.line1, "Song");
+    }
+
+    #[test]
+    fn changes_beyond_the_loaded_items_are_skipped() {
+        let mut queue = vec![create_item(1, "Song"), create_item(2, "Other")];
+        let changes = [
+            QueueChange {
+                operation: QueueOperation::Insert,
+                index: 5,
+                items: Some(vec![create_item(3, "Song")]),
+                count: None,
+            },
+            QueueChange {
+                operation: QueueOperation::Remove,
+                index: 1,
+                items: None,
+                count: Some(3),
+            },
+        ];
+
+        assert_eq!(apply_queue_changes(&mut queue, &changes), Some(true));
+        assert_eq!(get_ids(&queue), [1]);
+    }
+}
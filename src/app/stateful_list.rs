@@ -1,11 +1,14 @@
-use ratatui::widgets::ListState;
+use ratatui::{layout::{Margin, Rect}, widgets::ListState};
 
 pub struct StatefulList<T> {
     pub title: Option<String>,
     pub state: ListState,
     pub items: Option<Vec<T>>,
+    pub area: Rect,
     item_line_count: Vec<usize>,
     page_lines: usize,
+    page_size: Option<usize>,
+    wrap: bool,
 }
 
 impl<T> StatefulList<T> {
@@ -14,29 +17,64 @@ impl<T> StatefulList<T> {
             title: None,
             state: ListState::default(),
             items: None,
+            area: Rect::default(),
             item_line_count: Vec::new(),
             page_lines: 0,
+            page_size: None,
+            wrap: false,
+        }
+    }
+
+    // Pages by a fixed number of items instead of by the lines that fit in the area,
+    // wrapping moves from the last item to the first and the other way around
+    pub fn with_navigation(page_size: Option<usize>, wrap: bool) -> StatefulList<T> {
+        StatefulList {
+            page_size,
+            wrap,
+            ..Self::new()
         }
     }
 
     pub fn next(&mut self) {
         if let Some(item_count) = self.items.as_ref().map(|items| items.len()) {
             let next = self.state.selected()
-                .map(|i| if item_count > i + 1 { i + 1 } else { i });
+                .map(|i| if item_count > i + 1 { i + 1 } else if self.wrap { 0 } else { i });
 
             self.state.select(next);
         }
     }
 
     pub fn prev(&mut self) {
-        if let Some(_) = self.items {
+        if let Some(item_count) = self.items.as_ref().map(|items| items.len()) {
             let prev = self.state.selected()
-                .map(|i| if i > 0 { i - 1 } else { 0 });
+                .map(|i| if i > 0 { i - 1 } else if self.wrap { item_count.saturating_sub(1) } else { 0 });
 
             self.state.select(prev);
         }
     }
 
+    pub fn scroll(&mut self, up: bool) {
+        if let Some(selected) = self.state.selected() {
+            let item_count = self.items.as_ref().map(|items| items.len()).unwrap_or_default();
+
+            // The scroll wheel stops at the ends, also when wrapping
+            if up && selected > 0 {
+                self.prev();
+            } else if !up && selected + 1 < item_count {
+                self.next();
+            }
+        } else if let Some(items) = self.items.as_ref() {
+            // Without selection only the visible part of the list is moved
+            let offset = self.state.offset();
+
+            *self.state.offset_mut() = if up {
+                offset.saturating_sub(1)
+            } else {
+                (offset + 1).min(items.len().saturating_sub(1))
+            };
+        }
+    }
+
     pub fn select(&mut self, index: Option<usize>) {
         if index.is_some() {
             self.state.select(index);
@@ -51,11 +89,13 @@ impl<T> StatefulList<T> {
     }
 
     pub fn select_first(&mut self) {
-        self.select(Some(0));
+        if !self.is_empty() {
+            self.select(Some(0));
+        }
     }
 
     pub fn select_last(&mut self) {
-        if let Some(items) = self.items.as_ref() {
+        if let Some(items) = self.items.as_ref().filter(|items| !items.is_empty()) {
             let last = items.len() - 1;
 
             self.state.select(Some(last));
@@ -63,14 +103,23 @@ impl<T> StatefulList<T> {
     }
 
     pub fn select_next_page(&mut self) {
-        if let Some(selected) = self.state.selected() {
+        if self.is_empty() {
+            return;
+        }
+
+        if let (Some(page_size), Some(selected)) = (self.page_size, self.state.selected()) {
+            // Whole items are counted, rendering keeps the selected item visible
+            let last = self.items.as_ref().map(|items| items.len().saturating_sub(1)).unwrap_or_default();
+
+            self.state.select(Some((selected + page_size).min(last)));
+        } else if let Some(selected) = self.state.selected().filter(|_| self.page_lines > 0) {
             let offset = self.state.offset();
             let item_count = self.items.as_ref().unwrap().len();
             let mut counted_lines: usize = 0;
 
             if offset < selected {
                 for i in offset..selected {
-                    counted_lines += self.item_line_count[i];
+                    counted_lines += self.get_line_count(i);
                 }
 
                 if counted_lines >= self.page_lines {
@@ -80,15 +129,18 @@ impl<T> StatefulList<T> {
 
             counted_lines = 0;
 
+            // Move at least one item, also when an item fills the page on its own
+            let next = (selected + 1).min(item_count - 1);
+
             for i in selected..item_count {
-                counted_lines += self.item_line_count[i];
+                counted_lines += self.get_line_count(i);
 
                 if counted_lines == self.page_lines {
-                    self.state.select(Some(i));
+                    self.state.select(Some(i.max(next)));
                     break;
                 } else if counted_lines > self.page_lines {
                     // Skip the incomplete item at the end
-                    self.state.select(Some(i - 1));
+                    self.state.select(Some(i.saturating_sub(1).max(next)));
                     break;
                 }
             }
@@ -100,7 +152,13 @@ impl<T> StatefulList<T> {
     }
 
     pub fn select_prev_page(&mut self) {
-        if let Some(selected) = self.state.selected() {
+        if self.is_empty() {
+            return;
+        }
+
+        if let (Some(page_size), Some(selected)) = (self.page_size, self.state.selected()) {
+            self.state.select(Some(selected.saturating_sub(page_size)));
+        } else if let Some(selected) = self.state.selected().filter(|_| self.page_lines > 0) {
             let mut offset = self.state.offset();
             let mut counted_lines: usize = 0;
 
@@ -108,22 +166,25 @@ impl<T> StatefulList<T> {
                 offset = selected;
             }
 
+            // Move at least one item, also when an item fills the page on its own
+            let prev = selected.saturating_sub(1);
+
             for i in (0..=selected).rev() {
-                counted_lines += self.item_line_count[i];
+                counted_lines += self.get_line_count(i);
 
                 if offset == 0 {
                     self.select_first();
 
                     break;
                 } else if counted_lines == self.page_lines {
-                    *self.state.offset_mut() = offset;
-                    self.state.select(Some(offset));
+                    *self.state.offset_mut() = offset.min(prev);
+                    self.state.select(Some(offset.min(prev)));
 
                     break;
                 } else if counted_lines > self.page_lines {
                     // Skip the incomplete item at the end
-                    *self.state.offset_mut() = offset + 1;
-                    self.state.select(Some(offset + 1));
+                    *self.state.offset_mut() = (offset + 1).min(prev);
+                    self.state.select(Some((offset + 1).min(prev)));
 
                     break;
                 }
@@ -133,6 +194,23 @@ impl<T> StatefulList<T> {
         }
     }
 
+    // Recounts the lines of the items on the next draw
+    pub fn refresh_paging(&mut self) {
+        self.item_line_count.clear();
+        self.page_lines = 0;
+    }
+
+    // Items added after the last draw are counted as a single line,
+    // paging by lines waits for the line counts of the next draw after a reset
+    fn get_line_count(&self, index: usize) -> usize {
+        self.item_line_count.get(index).cloned().unwrap_or(1)
+    }
+
+    // Without items there is nothing to page through
+    fn is_empty(&self) -> bool {
+        self.items.as_ref().map(|items| items.is_empty()).unwrap_or(true)
+    }
+
     pub fn deselect(&mut self) {
         self.state.select(None);
     }
@@ -141,7 +219,7 @@ impl<T> StatefulList<T> {
         self.state.selected().is_some()
     }
 
-    pub fn prepare_paging(&mut self, page_lines: usize, f: fn(&T) -> usize) {
+    pub fn prepare_paging<F: Fn(&T) -> usize>(&mut self, page_lines: usize, f: F) {
         if page_lines != self.page_lines {
             let mut item_line_count = Vec::new();
 
@@ -158,6 +236,36 @@ impl<T> StatefulList<T> {
         }
     }
 
+    pub fn contains(&self, column: u16, row: u16) -> bool {
+        let area = self.area;
+
+        column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+    }
+
+    pub fn get_index_at(&self, column: u16, row: u16) -> Option<usize> {
+        // Exclude border
+        let inner = self.area.inner(&Margin { horizontal: 1, vertical: 1 });
+
+        if column < inner.x || column >= inner.x + inner.width || row < inner.y || row >= inner.y + inner.height {
+            return None;
+        }
+
+        let item_count = self.items.as_ref()?.len();
+        let mut line = (row - inner.y) as usize;
+
+        for index in self.state.offset()..item_count {
+            let line_count = self.get_line_count(index);
+
+            if line < line_count {
+                return Some(index);
+            }
+
+            line -= line_count;
+        }
+
+        None
+    }
+
     pub fn get_selected_item(&self) -> Option<&T> {
         let index = self.state.selected()?;
 
@@ -170,3 +278,175 @@ impl<T> StatefulList<T> {
         self.items.as_mut()?.get_mut(index)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_LINES: usize = 4;
+
+    // Each item holds its own line count
+    fn create_list(line_counts: &[usize], selected: usize, offset: usize) -> StatefulList<usize> {
+        let mut list = StatefulList::new();
+
+        list.items = Some(line_counts.to_vec());
+        list.select(Some(selected));
+        *list.state.offset_mut() = offset;
+        draw(&mut list);
+
+        list
+    }
+
+    // Counts the lines like a draw does, the selection resets the paging
+    fn draw(list: &mut StatefulList<usize>) {
+        list.prepare_paging(PAGE_LINES, |line_count| *line_count);
+    }
+
+    fn assert_position(list: &StatefulList<usize>, selected: usize, offset: usize) {
+        assert_eq!(list.state.selected(), Some(selected));
+        assert_eq!(list.state.offset(), offset);
+    }
+
+    #[test]
+    fn page_down_over_mixed_line_counts() {
+        let line_counts = [1, 2, 1, 2, 2, 1, 1, 2, 1, 1];
+        // Start position and the expected selection and offset after paging down
+        let cases = [
+            ((0, 0), (2, 0)),
+            ((2, 0), (3, 0)),
+            ((3, 0), (4, 3)),
+            ((4, 3), (6, 3)),
+            ((6, 3), (8, 6)),
+            ((8, 6), (9, 6)),
+            ((9, 6), (9, 9)),
+        ];
+
+        for ((selected, offset), (expected_selected, expected_offset)) in cases {
+            let mut list = create_list(&line_counts, selected, offset);
+
+            list.select_next_page();
+            assert_position(&list, expected_selected, expected_offset);
+        }
+    }
+
+    #[test]
+    fn page_up_over_mixed_line_counts() {
+        let line_counts = [1, 2, 1, 2, 2, 1, 1, 2, 1, 1];
+        // Start position and the expected selection and offset after paging up
+        let cases = [
+            ((9, 6), (7, 7)),
+            ((7, 7), (5, 5)),
+            ((5, 5), (4, 4)),
+            ((4, 4), (3, 3)),
+            ((3, 3), (2, 2)),
+            ((2, 0), (0, 0)),
+            ((0, 0), (0, 0)),
+        ];
+
+        for ((selected, offset), (expected_selected, expected_offset)) in cases {
+            let mut list = create_list(&line_counts, selected, offset);
+
+            list.select_prev_page();
+            assert_position(&list, expected_selected, expected_offset);
+        }
+    }
+
+    #[test]
+    fn paging_moves_when_an_item_fills_the_page() {
+        let mut list = create_list(&[4, 4, 4], 0, 0);
+
+        list.select_next_page();
+        assert_eq!(list.state.selected(), Some(1));
+
+        let mut list = create_list(&[4, 4, 4], 2, 2);
+
+        list.select_prev_page();
+        assert_eq!(list.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn navigating_an_empty_list() {
+        for items in [None, Some(Vec::new())] {
+            let mut list: StatefulList<usize> = StatefulList::new();
+
+            list.items = items;
+            draw(&mut list);
+
+            list.next();
+            list.prev();
+            list.select_first();
+            list.select_last();
+            list.select_next_page();
+            list.select_prev_page();
+            list.scroll(true);
+            list.scroll(false);
+
+            assert_eq!(list.state.selected(), None);
+            assert_eq!(list.get_selected_item(), None);
+        }
+    }
+
+    fn create_three_items(wrap: bool, selected: usize) -> StatefulList<usize> {
+        let mut list = StatefulList::with_navigation(None, wrap);
+
+        list.items = Some(vec![1, 1, 1]);
+        list.select(Some(selected));
+
+        list
+    }
+
+    #[test]
+    fn next_and_prev_wrap_around() {
+        let mut list = create_three_items(true, 2);
+
+        list.next();
+        assert_eq!(list.state.selected(), Some(0));
+        list.next();
+        assert_eq!(list.state.selected(), Some(1));
+
+        let mut list = create_three_items(true, 0);
+
+        list.prev();
+        assert_eq!(list.state.selected(), Some(2));
+        list.prev();
+        assert_eq!(list.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn next_and_prev_stop_at_the_ends() {
+        let mut list = create_three_items(false, 2);
+
+        list.next();
+        assert_eq!(list.state.selected(), Some(2));
+        list.prev();
+        assert_eq!(list.state.selected(), Some(1));
+
+        let mut list = create_three_items(false, 0);
+
+        list.prev();
+        assert_eq!(list.state.selected(), Some(0));
+        list.next();
+        assert_eq!(list.state.selected(), Some(1));
+
+        // The default navigation doesn't wrap
+        let mut list: StatefulList<usize> = StatefulList::new();
+
+        list.items = Some(vec![1, 1, 1]);
+        list.select(Some(2));
+        list.next();
+        assert_eq!(list.state.selected(), Some(2));
+    }
+
+    #[test]
+    fn scrolling_stops_at_the_ends_when_wrapping() {
+        let mut list = create_three_items(true, 2);
+
+        list.scroll(false);
+        assert_eq!(list.state.selected(), Some(2));
+
+        let mut list = create_three_items(true, 0);
+
+        list.scroll(true);
+        assert_eq!(list.state.selected(), Some(0));
+    }
+}
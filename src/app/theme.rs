@@ -0,0 +1,169 @@
+use ratatui::style::{Color, Modifier};
+use serde::Deserialize;
+use std::{env, fs, path::Path, str::FromStr};
+
+pub struct Theme {
+    pub brand: Color,
+    pub gray: Color,
+    pub highlight_bg: Color,
+    pub gauge_bg: Color,
+    pub gauge_empty_bg: Color,
+    pub text_reset: Color,
+    // The order of the now playing metadata lines, by index, and their emphasis
+    pub now_playing: Vec<(usize, Modifier)>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    brand: Option<String>,
+    gray: Option<String>,
+    highlight_bg: Option<String>,
+    gauge_bg: Option<String>,
+    gauge_empty_bg: Option<String>,
+    text_reset: Option<String>,
+    now_playing: Option<Vec<MetadataLine>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetadataLine {
+    line: String,
+    style: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            brand: Color::Rgb(0x75, 0x75, 0xf3),
+            gray: Color::Rgb(0x80, 0x80, 0x80),
+            highlight_bg: Color::Rgb(0x75, 0x75, 0xf3),
+            gauge_bg: Color::Rgb(0x30, 0x30, 0x30),
+            gauge_empty_bg: Color::Rgb(0x30, 0x30, 0x30),
+            text_reset: Color::Reset,
+            now_playing: get_default_now_playing(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            brand: Color::Rgb(0x4b, 0x4b, 0xd6),
+            gray: Color::Rgb(0x5a, 0x5a, 0x5a),
+            highlight_bg: Color::Rgb(0xa8, 0xa8, 0xf8),
+            gauge_bg: Color::Rgb(0xd8, 0xd8, 0xd8),
+            gauge_empty_bg: Color::Rgb(0xd8, 0xd8, 0xd8),
+            text_reset: Color::Reset,
+            now_playing: get_default_now_playing(),
+        }
+    }
+
+    pub fn auto() -> Self {
+        if is_light_background() {
+            Self::light()
+        } else {
+            Self::dark()
+        }
+    }
+
+    pub fn load(path: &Path, base: Theme) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return base,
+        };
+        let theme_file = match toml::from_str::<ThemeFile>(&content) {
+            Ok(theme_file) => theme_file,
+            Err(err) => {
+                log::warn!("Ignoring {}: {}", path.display(), err);
+
+                return base;
+            }
+        };
+
+        // Colors missing from the file are taken from the base theme
+        Self {
+            brand: parse_color("brand", theme_file.brand, base.brand),
+            gray: parse_color("gray", theme_file.gray, base.gray),
+            highlight_bg: parse_color("highlight_bg", theme_file.highlight_bg, base.highlight_bg),
+            gauge_bg: parse_color("gauge_bg", theme_file.gauge_bg, base.gauge_bg),
+            gauge_empty_bg: parse_color("gauge_empty_bg", theme_file.gauge_empty_bg, base.gauge_empty_bg),
+            text_reset: parse_color("text_reset", theme_file.text_reset, base.text_reset),
+            now_playing: parse_now_playing(theme_file.now_playing, base.now_playing),
+        }
+    }
+}
+
+fn parse_color(name: &str, value: Option<String>, default: Color) -> Color {
+    match value {
+        // Accepts named colors, "#rrggbb" hex strings and ANSI color indices
+        Some(value) => Color::from_str(&value).unwrap_or_else(|_| {
+            log::warn!("Ignoring invalid color '{}' for {}", value, name);
+            default
+        }),
+        None => default,
+    }
+}
+
+// Title, artist and album
+fn get_default_now_playing() -> Vec<(usize, Modifier)> {
+    vec![(0, Modifier::BOLD), (1, Modifier::empty()), (2, Modifier::ITALIC)]
+}
+
+fn parse_now_playing(value: Option<Vec<MetadataLine>>, default: Vec<(usize, Modifier)>) -> Vec<(usize, Modifier)> {
+    let value = match value {
+        Some(value) => value,
+        None => return default,
+    };
+    let mut now_playing = Vec::new();
+
+    for metadata_line in value {
+        let index = match metadata_line.line.as_str() {
+            "line1" => 0,
+            "line2" => 1,
+            "line3" => 2,
+            _ => {
+                log::warn!("Ignoring now_playing, invalid line '{}'", metadata_line.line);
+                return default;
+            }
+        };
+        let modifier = match metadata_line.style.as_deref().unwrap_or("normal") {
+            "normal" => Modifier::empty(),
+            "bold" => Modifier::BOLD,
+            "italic" => Modifier::ITALIC,
+            "dim" => Modifier::DIM,
+            "underlined" => Modifier::UNDERLINED,
+            style => {
+                log::warn!("Ignoring now_playing, invalid style '{}' for {}", style, metadata_line.line);
+                return default;
+            }
+        };
+
+        if now_playing.iter().any(|(existing, _)| *existing == index) {
+            log::warn!("Ignoring now_playing, {} is listed twice", metadata_line.line);
+            return default;
+        }
+
+        now_playing.push((index, modifier));
+    }
+
+    if now_playing.is_empty() {
+        log::warn!("Ignoring now_playing without lines");
+        return default;
+    }
+
+    now_playing
+}
+
+fn is_light_background() -> bool {
+    // COLORFGBG is formatted as "fg;bg" or "fg;default;bg", using the ANSI color indices
+    let background = env::var("COLORFGBG").ok().and_then(|colorfgbg| {
+        colorfgbg.rsplit(';').next()?.parse::<u8>().ok()
+    });
+
+    matches!(background, Some(7) | Some(9..=15))
+}
@@ -1,40 +1,82 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Line},
     widgets::{block::{self, Block, Position, Title}, BorderType, Borders, Clear, Gauge, HighlightSpacing, List, ListItem, Padding, Paragraph},
 };
-use roon_api::transport::{State, Zone, Repeat, volume::Scale};
+use roon_api::transport::{State, Zone, Repeat, volume::{Scale, Volume}};
+use std::{borrow::Cow, io::{self, Write}, time::Instant};
+use unicode_bidi::{bidi_class, BidiClass, BidiInfo};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::{app::{App, View}, io::EndPoint};
+use crate::{app::{App, LayoutMode, View, keymap::Action}, io::{ConnectionState, EndPoint}};
 
-const ROON_BRAND_COLOR: Color = Color::Rgb(0x75, 0x75, 0xf3);
-const CUSTOM_GRAY: Color = Color::Rgb(0x80, 0x80, 0x80);
 const UNI_HIGHLIGHT_SYMBOL: &str = " \u{23f5} ";
 const UNI_CHECKED_SYMBOL: &str = "\u{1F5F9}";
 const UNI_UNCHECKED_SYMBOL: &str = "\u{2610}";
+const UNI_PLAYING_SYMBOL: &str = "\u{266A}";
+const UNI_STANDBY_SYMBOL: &str = "\u{23FB}";
+const UNI_REPEAT_SYMBOL: &str = "\u{21BB}";
+const UNI_SHUFFLE_SYMBOL: &str = "\u{21C4}";
+const UNI_ELLIPSIS: &str = "\u{2026}";
 const HIGHLIGHT_SYMBOL: &str = " > ";
+const PLAYING_SYMBOL: &str = "*";
+const STANDBY_SYMBOL: &str = "o";
+const REPEAT_SYMBOL: &str = "R";
+const SHUFFLE_SYMBOL: &str = "S";
+const ELLIPSIS: &str = "...";
+const UNI_BREADCRUMB_SEPARATOR: &str = " \u{203A} ";
+const BREADCRUMB_SEPARATOR: &str = " > ";
 const CHECKED_SYMBOL: &str = "+";
 const UNCHECKED_SYMBOL: &str = "-";
+const MARQUEE_DELAY_MS: u128 = 2000;
+const MARQUEE_STEP_MS: u128 = 500;
+const MARQUEE_GAP: &str = "   ";
+const UNI_SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+const SPINNER_STEP_MS: u128 = 100;
+const LIVE_PULSE_STEP_MS: u128 = 100;
+const LIVE_LABEL: &str = " LIVE ";
+const VOLUME_BAR_WIDTH: usize = 7;
+const AUTO_VERTICAL_WIDTH: u16 = 80;
+// Partially filled cells, in eighths
+const UNI_VOLUME_BAR_PARTS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
 
 pub fn draw(frame: &mut Frame, app: &mut App) {
     let size = frame.size();
 
     // Surrounding block
     let title = format!(" Roon TUI v{} ", env!("CARGO_PKG_VERSION"));
-    let subtitle = if let Some(name) = app.core_name.as_ref() {
-        format!(" {} ", name)
-    } else {
+    let subtitle = match &app.connection {
+        ConnectionState::Found(name) => format!(" {} ", name),
+        ConnectionState::Discovering => " Searching for a Roon Server... ".to_owned(),
+        ConnectionState::Connecting(ip) => format!(" Connecting to the Roon Server at {}... ", ip),
+        ConnectionState::Lost(name) => format!(" Connection to {} lost ", name),
+        ConnectionState::Retrying(secs) => format!(" No Roon Server paired/found, retrying in {}s ", secs),
+    };
+
+    if app.core_name.is_none() {
         app.select_view(None);
-        " No Roon Server paired/found ".to_owned()
+    }
+
+    // A transient status takes the place of the help hint
+    let hint = match app.status.as_ref() {
+        Some((status, is_error, _)) => {
+            let style = if *is_error {
+                Style::default().fg(app.theme.brand).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(app.theme.brand)
+            };
+
+            Span::styled(format!(" {} ", status), style)
+        }
+        None => Span::styled(" Ctrl-h for Help ", Style::default().fg(app.theme.text_reset)),
     };
-    let hint = Title::from(
-            Span::styled(" Ctrl-h for Help ", Style::default().fg(Color::Reset))
-        )
+    let hint = Title::from(hint)
         .position(Position::Bottom)
         .alignment(Alignment::Center);
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(get_border_view_style(app, None))
         .title(Span::styled(title, get_text_view_style(app, None)))
@@ -43,6 +85,15 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         .title_alignment(Alignment::Center)
         .border_type(BorderType::Plain);
 
+    if app.monitor {
+        let badge = Title::from(
+                Span::styled(" MONITOR ", Style::default().fg(app.theme.brand).add_modifier(Modifier::BOLD))
+            )
+            .alignment(Alignment::Right);
+
+        block = block.title(badge);
+    }
+
     frame.render_widget(block, size);
 
     let chunks = Layout::default()
@@ -52,29 +103,90 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         .constraints([Constraint::Min(8), Constraint::Length(7)].as_ref())
         .split(size);
 
-    // Top two inner blocks
+    app.vertical_layout = match app.layout_mode {
+        LayoutMode::Horizontal => false,
+        LayoutMode::Vertical => true,
+        LayoutMode::Auto => size.width < AUTO_VERTICAL_WIDTH,
+    };
+
+    // Top two inner blocks, stacked on narrow screens
+    let top_direction = if app.vertical_layout {Direction::Vertical} else {Direction::Horizontal};
     let top_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .direction(top_direction)
+        .constraints([Constraint::Percentage(app.split), Constraint::Percentage(100 - app.split)].as_ref())
         .split(chunks[0]);
 
-    draw_browse_view(frame, top_chunks[0], app);
-    draw_queue_view(frame, top_chunks[1], app);
-    draw_now_playing_view(frame, chunks[1], app);
+    if app.full_screen && app.core_name.is_some() {
+        let area = Layout::default()
+            .horizontal_margin(2)
+            .vertical_margin(1)
+            .constraints([Constraint::Percentage(100)].as_ref())
+            .split(size)[0];
+
+        // Hidden lists can't be clicked
+        app.browse.area = Rect::default();
+        app.queue.area = Rect::default();
+
+        draw_now_playing_view(frame, area, app);
+    } else if app.queue_hidden {
+        // A hidden list can't be clicked
+        app.queue.area = Rect::default();
+
+        draw_browse_view(frame, chunks[0], app);
+        draw_now_playing_view(frame, chunks[1], app);
+    } else {
+        draw_browse_view(frame, top_chunks[0], app);
+        draw_queue_view(frame, top_chunks[1], app);
+        draw_now_playing_view(frame, chunks[1], app);
+    }
+
+    // The stacked views are low, popups cover both of them
+    let popup_area = if app.vertical_layout {chunks[0]} else {top_chunks[1]};
 
     match app.selected_view {
         Some(View::Prompt) => draw_prompt_view(frame, top_chunks[0], app),
-        Some(View::Zones) => draw_zones_view(frame, top_chunks[1], app),
+        Some(View::Zones) => draw_zones_view(frame, popup_area, app),
+        Some(View::Bookmarks) => draw_bookmarks_view(frame, top_chunks[0], app),
+        Some(View::Confirm) => draw_confirm_view(frame, popup_area, app),
+        Some(View::ZoneSettings) => draw_zone_settings_view(frame, chunks[1], app),
+        Some(View::Overview) => draw_overview_view(frame, popup_area, app),
         Some(View::Grouping) | Some(View::GroupingPreset) => {
-            draw_grouping_view(frame, top_chunks[1], app);
+            draw_grouping_view(frame, popup_area, app);
         }
         Some(View::Help) => draw_help_view(frame, size, app),
         _ => (),
     }
 }
 
+// Returns true when the screen has to be cleared and redrawn
+pub fn draw_graphics<W: Write>(writer: &mut W, app: &mut App) -> io::Result<bool> {
+    // The help view covers the album art
+    let area = if app.art_area.area() == 0 || app.selected_view == Some(View::Help) {
+        None
+    } else {
+        Some(app.art_area)
+    };
+
+    match app.album_art.as_mut() {
+        Some(album_art) => album_art.draw_graphics(writer, area),
+        None => Ok(false),
+    }
+}
+
 fn draw_browse_view(frame: &mut Frame, area: Rect, app: &mut App) {
-    let browse_title = format!("{}", app.browse.title.as_deref().unwrap_or("Browse"));
+    let browse_title = app.browse.title.as_deref().unwrap_or("Browse");
+    // Leave room for the spinner and the progress
+    let max_len = area.width.saturating_sub(16) as usize;
+    let browse_title = if app.browse_breadcrumb.len() > 1 {
+        get_breadcrumb(&app.browse_breadcrumb, max_len, app.no_unicode_symbols)
+    } else {
+        browse_title.to_owned()
+    };
+    let browse_title = if app.browse_loading {
+        format!("{} {}", browse_title, get_spinner(app))
+    } else {
+        browse_title
+    };
     let page_lines = area.height.saturating_sub(2) as usize;  // Exclude border
     let view = Some(&View::Browse);
     let mut block = Block::default()
@@ -85,27 +197,25 @@ fn draw_browse_view(frame: &mut Frame, area: Rect, app: &mut App) {
             get_text_view_style(&app, view),
         ));
 
-    app.browse.prepare_paging(page_lines, |item| if item.subtitle.is_none() {1} else {2});
+    app.browse.area = area;
+    let compact = app.compact;
+
+    app.browse.prepare_paging(page_lines, |item| {
+        if compact || item.subtitle.as_deref().unwrap_or_default().is_empty() {1} else {2}
+    });
 
     if let Some(browse_items) = &app.browse.items {
-        let secondary_style = if app.get_selected_view().is_some() {
-            Style::default().add_modifier(Modifier::ITALIC)
-        } else {
-            Style::default().fg(CUSTOM_GRAY).add_modifier(Modifier::ITALIC)
-        };
+        let secondary_style = get_secondary_style(app);
         let items: Vec<ListItem> = browse_items
             .iter()
             .map(|item| {
-                let subtitle = item.subtitle.as_ref().filter(|s| !s.is_empty());
+                let subtitle = item.subtitle.as_ref().filter(|s| !s.is_empty() && !app.compact);
                 let mut lines = vec![
-                    Line::from(Span::styled(&item.title, get_text_view_style(&app, view)))
+                    Line::from(Span::styled(get_display_text(&item.title), get_text_view_style(&app, view)))
                 ];
 
                 if let Some(subtitle) = subtitle {
-                    lines.push(Line::from(Span::styled(
-                        format!("  {}", subtitle),
-                        secondary_style,
-                    )));
+                    lines.push(get_secondary_line(subtitle, secondary_style));
                 }
 
                 ListItem::new(lines)
@@ -118,7 +228,7 @@ fn draw_browse_view(frame: &mut Frame, area: Rect, app: &mut App) {
             .block(Block::default().borders(Borders::ALL))
             .highlight_style(
                 Style::default()
-                    .bg(ROON_BRAND_COLOR)
+                    .bg(app.theme.highlight_bg)
                     .add_modifier(Modifier::BOLD)
             )
             .highlight_symbol(highlight_symbol)
@@ -127,30 +237,53 @@ fn draw_browse_view(frame: &mut Frame, area: Rect, app: &mut App) {
         // We can now render the item list
         frame.render_stateful_widget(list, area, &mut app.browse.state);
 
+        if browse_items.is_empty() && !app.browse_loading && app.browse_filter.is_none() {
+            let inner = area.inner(&Margin { horizontal: 1, vertical: 1 });
+            let line = Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner };
+            let text = Paragraph::new(Span::styled("(empty)", Style::default().fg(app.theme.gray)))
+                .alignment(Alignment::Center);
+
+            frame.render_widget(text, line);
+        }
+
         if let Some(View::Browse) = app.selected_view.as_ref() {
             let len = browse_items.len();
 
-            if len > 0 {
+            if let Some(selected) = app.browse.state.selected().filter(|_| len > 0) {
                 let progress = format!(
                     "{}/{}",
-                    app.browse.state.selected().unwrap() + 1,
+                    selected + 1,
                     len
                 );
 
                 block = block.title(
                     Title::from(
-                        Span::styled(progress, Style::default().fg(Color::Reset))
+                        Span::styled(progress, Style::default().fg(app.theme.text_reset))
                     ).alignment(Alignment::Right)
                 );
 
                 if !app.input.is_empty() {
+                    let input = match get_input_match(app) {
+                        Some((position, count)) => format!("{} match {}/{}", app.input, position, count),
+                        None => app.input.to_owned(),
+                    };
+
                     block = block.title(
                         Title::from(
-                            Span::styled(app.input.as_str(), Style::default().fg(Color::Reset))
+                            Span::styled(input, Style::default().fg(app.theme.text_reset))
                         ).position(Position::Bottom)
                     );
                 }
             }
+
+            // Also show the filter without any matching items
+            if let Some(filter) = app.browse_filter.as_ref() {
+                block = block.title(
+                    Title::from(
+                        Span::styled(format!("/{}", filter), Style::default().fg(app.theme.text_reset))
+                    ).position(Position::Bottom)
+                );
+            }
         }
     }
 
@@ -164,46 +297,75 @@ fn draw_queue_view(frame: &mut Frame, area: Rect, app: &mut App) {
         .borders(Borders::ALL)
         .border_style(get_border_view_style(&app, view))
         .title(Span::styled(
-            "Queue",
+            if app.queue_loading {format!("{} Queue", get_spinner(app))} else {"Queue".to_owned()},
             get_text_view_style(&app, view),
         ))
         .title_alignment(Alignment::Right);
 
+    if let Some(settings) = app.selected_zone.as_ref().map(|zone| &zone.settings) {
+        let (repeat_symbol, shuffle_symbol) = if app.no_unicode_symbols {
+            (REPEAT_SYMBOL, SHUFFLE_SYMBOL)
+        } else {
+            (UNI_REPEAT_SYMBOL, UNI_SHUFFLE_SYMBOL)
+        };
+        let repeat = match settings.repeat {
+            Repeat::All => Some(repeat_symbol.to_owned()),
+            Repeat::One => Some(format!("{}1", repeat_symbol)),
+            Repeat::Off => None,
+        };
+        let shuffle = if settings.shuffle {Some(shuffle_symbol.to_owned())} else {None};
+        let symbols = [repeat, shuffle].into_iter().flatten().collect::<Vec<_>>().join(" ");
+
+        // Subtle, to not compete with the other titles
+        if !symbols.is_empty() {
+            block = block.title(
+                Title::from(
+                    Span::styled(symbols, Style::default().fg(app.theme.gray))
+                ).alignment(Alignment::Left)
+            );
+        }
+    }
+
     if let Some(queue_mode) = app.queue_mode {
         block = block.title(
             Title::from(
-                Span::styled(queue_mode, Style::default().fg(Color::Reset))
+                Span::styled(queue_mode, Style::default().fg(app.theme.text_reset))
             ).position(Position::Bottom)
         );
     }
 
-    app.queue.prepare_paging(page_lines, |item| if item.two_line.line2.is_empty() {1} else {2});
+    if app.queue_duration > 0 {
+        block = block.title(
+            Title::from(
+                Span::styled(get_time_string(app.queue_duration), Style::default().fg(app.theme.text_reset))
+            ).position(Position::Bottom).alignment(Alignment::Right)
+        );
+    }
+
+    app.queue.area = area;
+    let compact = app.compact;
+
+    app.queue.prepare_paging(page_lines, |item| if compact || item.two_line.line2.is_empty() {1} else {2});
 
     if let Some(queue_items) = &app.queue.items {
         let item_len = area.width.saturating_sub(6) as usize;
-        let secondary_style = if app.get_selected_view().is_some() {
-            Style::default().add_modifier(Modifier::ITALIC)
-        } else {
-            Style::default().fg(CUSTOM_GRAY).add_modifier(Modifier::ITALIC)
-        };
+        let ellipsis = if app.no_unicode_symbols {ELLIPSIS} else {UNI_ELLIPSIS};
+        let secondary_style = get_secondary_style(app);
         let items: Vec<ListItem> = queue_items
             .iter()
             .map(|item| {
                 let duration = get_time_string(item.length);
                 let max_len = item_len.saturating_sub(duration.len() + 1);
-                let (line1_len, line1) = trim_string(&item.two_line.line1, max_len);
+                let (line1_len, line1) = trim_string(&item.two_line.line1, max_len, ellipsis);
                 let pad_len = item_len.saturating_sub(line1_len + duration.len());
                 let pad: String = (0..pad_len).map(|_| ' ').collect();
-                let line1 = format!("{}{}{}", line1, pad, duration);
+                let line1 = format!("{}{}{}", get_display_text(&line1), pad, duration);
                 let mut lines = vec![
                     Line::from(Span::styled(line1, get_text_view_style(&app, view))),
                 ];
 
-                if !item.two_line.line2.is_empty() {
-                    lines.push(Line::from(Span::styled(
-                        format!("  {}", item.two_line.line2),
-                        secondary_style,
-                    )));
+                if !item.two_line.line2.is_empty() && !app.compact {
+                    lines.push(get_secondary_line(&item.two_line.line2, secondary_style));
                 }
 
                 ListItem::new(lines)
@@ -216,7 +378,7 @@ fn draw_queue_view(frame: &mut Frame, area: Rect, app: &mut App) {
             .block(Block::default().borders(Borders::ALL))
             .highlight_style(
                 Style::default()
-                    .bg(ROON_BRAND_COLOR)
+                    .bg(app.theme.highlight_bg)
                     .add_modifier(Modifier::BOLD)
             )
             .highlight_symbol(highlight_symbol)
@@ -228,16 +390,16 @@ fn draw_queue_view(frame: &mut Frame, area: Rect, app: &mut App) {
         if let Some(View::Queue) = app.selected_view.as_ref() {
             let len = queue_items.len();
 
-            if len > 0 {
+            if let Some(selected) = app.queue.state.selected().filter(|_| len > 0) {
                 let progress = format!(
                     "{}/{}",
-                    app.queue.state.selected().unwrap() + 1,
+                    selected + 1,
                     len
                 );
 
                 block = block.title(
                     Title::from(
-                        Span::styled(progress, Style::default().fg(Color::Reset))
+                        Span::styled(progress, Style::default().fg(app.theme.text_reset))
                     ).alignment(Alignment::Left)
                 );
             }
@@ -245,7 +407,7 @@ fn draw_queue_view(frame: &mut Frame, area: Rect, app: &mut App) {
             if let Some(queue_time_remaining) = get_queue_time_remaining(&app) {
                 block = block.title(
                     Title::from(
-                        Span::styled(queue_time_remaining, Style::default().fg(Color::Reset))
+                        Span::styled(queue_time_remaining, Style::default().fg(app.theme.text_reset))
                     ).alignment(Alignment::Left)
                 );
             }
@@ -255,8 +417,12 @@ fn draw_queue_view(frame: &mut Frame, area: Rect, app: &mut App) {
     frame.render_widget(block, area);
 }
 
-fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &mut App) {
     let view = Some(&View::NowPlaying);
+
+    app.gauge_area = Rect::default();
+    app.art_area = Rect::default();
+
     let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(get_border_view_style(app, view))
@@ -273,14 +439,20 @@ fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(3), Constraint::Length(2)].as_ref())
             .split(area);
+        let art_height = vert_chunks[0].height.saturating_sub(1);  // Exclude top border
+        let art_width = match app.album_art.as_ref() {
+            // Terminal cells are about twice as high as wide
+            Some(album_art) if album_art.has_image() && zone.now_playing.is_some() => art_height * 2 + 2,
+            _ => 0,
+        };
         let hor_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(20), Constraint::Length(14)].as_ref())
+            .constraints([Constraint::Length(art_width), Constraint::Min(20), Constraint::Length(14)].as_ref())
             .split(vert_chunks[0]);
         let style = if app.get_selected_view().is_some() {
-            Style::default().fg(Color::Reset)
+            Style::default().fg(app.theme.text_reset)
         } else {
-            Style::default().fg(CUSTOM_GRAY)
+            Style::default().fg(app.theme.gray)
         };
 
         let display_name = match app.matched_preset.as_ref() {
@@ -303,38 +475,69 @@ fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
                     top: 1,
                     bottom: 0,
                 });
-            let lines = vec![
-                Line::from(Span::styled(
-                    &now_playing.three_line.line1,
-                    style.add_modifier(Modifier::BOLD),
-                )),
-                Line::from(Span::styled(
-                    &now_playing.three_line.line2,
-                    style,
-                )),
-                Line::from(Span::styled(
-                    &now_playing.three_line.line3,
-                    style.add_modifier(Modifier::ITALIC),
-                )),
+            let max_len = hor_chunks[1].width.saturating_sub(4) as usize;    // Exclude padding
+            let step = (app.marquee_start.elapsed().as_millis().saturating_sub(MARQUEE_DELAY_MS) / MARQUEE_STEP_MS) as usize;
+            let three_line = [
+                &now_playing.three_line.line1,
+                &now_playing.three_line.line2,
+                &now_playing.three_line.line3,
             ];
+            let mut lines = app.theme.now_playing.iter()
+                .map(|(index, modifier)| {
+                    Line::from(Span::styled(
+                        get_marquee_text(&get_display_text(three_line[*index]), max_len, step),
+                        style.add_modifier(*modifier),
+                    ))
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(next) = get_next_track(app) {
+                let ellipsis = if app.no_unicode_symbols {ELLIPSIS} else {UNI_ELLIPSIS};
+                let (_, next) = trim_string(&format!("Next: {}", next), max_len, ellipsis);
+
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    get_display_text(&next).into_owned(),
+                    Style::default().fg(app.theme.gray),
+                )));
+            }
+
             let text = Paragraph::new(lines)
                 .block(metadata_block);
 
-            frame.render_widget(text, hor_chunks[0]);
+            frame.render_widget(text, hor_chunks[1]);
 
-            let duration = now_playing.length.unwrap_or_default();
-            let seek_position = if let Some(zone_seek) = app.zone_seek.as_ref() {
-                if zone_seek.seek_position.is_some() {
-                    zone_seek.seek_position
-                } else {
-                    now_playing.seek_position
+            if art_width > 0 {
+                // Skip border and padding
+                let art_area = Rect {
+                    x: hor_chunks[0].x + 2,
+                    y: hor_chunks[0].y + 1,
+                    width: hor_chunks[0].width.saturating_sub(2),
+                    height: art_height,
+                };
+                let no_unicode_symbols = app.no_unicode_symbols;
+
+                if let Some(album_art) = app.album_art.as_mut() {
+                    if album_art.is_blocks() {
+                        let lines = album_art.get_block_lines(art_area.width, art_area.height, no_unicode_symbols);
+
+                        frame.render_widget(Paragraph::new(lines), art_area);
+                    } else {
+                        // Drawn by draw_graphics, bypassing the frame buffer
+                        app.art_area = art_area;
+                    }
                 }
-            } else {
-                now_playing.seek_position
-            };
+            }
+
+            let duration = now_playing.length.unwrap_or_default();
+            let seek_position = app.get_seek_position();
 
             draw_progress_gauge(frame, vert_chunks[1], app, view, duration, seek_position);
 
+            // Store the area of the bar itself, excluding the padding, to seek by mouse click
+            app.gauge_area = vert_chunks[1].inner(&Margin { horizontal: 2, vertical: 0 });
+            app.gauge_area.height = 1;
+
             let play_state_title = match zone.state {
                 State::Loading => "Loading",
                 State::Paused => "Paused",
@@ -346,6 +549,19 @@ fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
                 State::Stopped => "Stopped",
             };
 
+            let play_state_title = match app.sleep_timer {
+                Some(sleep_timer) => {
+                    let remaining = sleep_timer.saturating_duration_since(Instant::now());
+
+                    format!("{} | Sleep in {}", play_state_title, get_time_string(remaining.as_secs() as u32))
+                }
+                None => play_state_title.to_owned(),
+            };
+            let play_state_title = match app.alarm.as_deref() {
+                Some(alarm) => format!("{} | Alarm {}", play_state_title, alarm),
+                None => play_state_title,
+            };
+
             block = block.title(Span::styled(
                 play_state_title,
                 get_text_view_style(app, view),
@@ -361,7 +577,7 @@ fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
             let text = Paragraph::new("Go find something to play!")
                 .block(msg_block).alignment(Alignment::Center);
 
-            frame.render_widget(text, hor_chunks[0]);
+            frame.render_widget(text, hor_chunks[1]);
         }
 
         let status_block = Block::default()
@@ -371,10 +587,10 @@ fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
                 top: 1,
                 bottom: 0,
             });
-        let text = Paragraph::new(get_status_lines(zone, style))
+        let text = Paragraph::new(get_status_lines(zone, style, app.volume_bar, app.no_unicode_symbols))
             .block(status_block).alignment(Alignment::Right);
 
-        frame.render_widget(text, hor_chunks[1]);
+        frame.render_widget(text, hor_chunks[2]);
     } else {
         let msg_block = Block::default()
             .padding(Padding {
@@ -395,6 +611,14 @@ fn draw_now_playing_view(frame: &mut Frame, area: Rect, app: &App) {
         frame.render_widget(text, area);
     }
 
+    if let Some(message) = app.message {
+        block = block.title(
+            Title::from(
+                Span::styled(message, Style::default().fg(app.theme.text_reset))
+            ).alignment(Alignment::Center)
+        );
+    }
+
     frame.render_widget(block, area);
 }
 
@@ -404,20 +628,28 @@ fn draw_progress_gauge(
     app: &App,
     view: Option<&View>,
     duration: u32,
-    seek_position: Option<i64>,
+    seek_position: Option<f64>,
 ) -> Option<()> {
-    let elapsed = seek_position? as u32;
-    let progress = if duration > 0 {elapsed * 100 / duration} else {0};
-    let elapsed = get_time_string(elapsed);
-    let label = if duration > 0 {
-        format!("{} / {}", elapsed, get_time_string(duration))
+    if duration == 0 {
+        draw_live_gauge(frame, area, app, view);
+
+        return Some(());
+    }
+
+    let seek_position = seek_position?;
+    let progress = (seek_position / duration as f64).clamp(0.0, 1.0);
+    let elapsed = get_time_string(seek_position as u32);
+    let label = if app.remaining_time {
+        let remaining = (duration as f64 - seek_position).max(0.0);
+
+        format!("{} / -{}", elapsed, get_time_string(remaining as u32))
     } else {
-        elapsed
+        format!("{} / {}", elapsed, get_time_string(duration))
     };
     let style = if app.get_selected_view().is_some() {
-        Style::default().fg(Color::Reset)
+        Style::default().fg(app.theme.text_reset)
     } else {
-        Style::default().fg(CUSTOM_GRAY)
+        Style::default().fg(app.theme.gray)
     };
     let gauge = Gauge::default()
         .block(Block::default().padding(Padding {
@@ -427,7 +659,7 @@ fn draw_progress_gauge(
             bottom: 1,
         }))
         .gauge_style(get_gauge_view_style(app, view))
-        .percent(progress as u16)
+        .ratio(progress)
         .label(Span::styled(label, style.add_modifier(Modifier::BOLD)));
 
     frame.render_widget(gauge, area);
@@ -435,6 +667,143 @@ fn draw_progress_gauge(
     Some(())
 }
 
+// Streams without a length get a pulse moving along the bar while playing
+fn draw_live_gauge(frame: &mut Frame, area: Rect, app: &App, view: Option<&View>) {
+    // Same padding as the regular gauge
+    let area = Rect {
+        x: area.x + 2,
+        y: area.y,
+        width: area.width.saturating_sub(4),
+        height: area.height.min(1),
+    };
+    let width = area.width as usize;
+
+    if width == 0 {
+        return;
+    }
+
+    let gauge_style = get_gauge_view_style(app, view);
+    let empty_bg = gauge_style.bg.unwrap_or(app.theme.gauge_empty_bg);
+    let pulse_bg = gauge_style.fg.unwrap_or(app.theme.gauge_bg);
+    let text_style = if app.get_selected_view().is_some() {
+        Style::default().fg(app.theme.text_reset)
+    } else {
+        Style::default().fg(app.theme.gray)
+    };
+    let is_playing = app.selected_zone.as_ref().map(|zone| zone.state == State::Playing).unwrap_or_default();
+    let pulse_width = (width / 5).max(1);
+    let pulse_end = if is_playing {
+        (app.spinner_start.elapsed().as_millis() / LIVE_PULSE_STEP_MS) as usize % (width + pulse_width)
+    } else {
+        0
+    };
+    let label_start = width.saturating_sub(LIVE_LABEL.len()) / 2;
+    let spans = (0..width)
+        .map(|index| {
+            let char = index.checked_sub(label_start)
+                .and_then(|offset| LIVE_LABEL.chars().nth(offset))
+                .unwrap_or(' ');
+            let bg = if index < pulse_end && index + pulse_width >= pulse_end {pulse_bg} else {empty_bg};
+
+            Span::styled(char.to_string(), text_style.bg(bg).add_modifier(Modifier::BOLD))
+        })
+        .collect::<Vec<_>>();
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+// The spinner frame for the time spent loading
+fn get_spinner(app: &App) -> &'static str {
+    let frames: &[&'static str] = if app.no_unicode_symbols {&SPINNER_FRAMES} else {&UNI_SPINNER_FRAMES};
+    let step = (app.spinner_start.elapsed().as_millis() / SPINNER_STEP_MS) as usize;
+
+    frames[step % frames.len()]
+}
+
+// Returns the position of the selected item within the matches of the typed input
+fn get_input_match(app: &App) -> Option<(usize, usize)> {
+    let (input, matches) = app.browse_input_matches.as_ref()?;
+
+    if *input != app.input {
+        return None;
+    }
+
+    let selected = app.browse.state.selected()?;
+    let position = matches.iter().position(|index| *index == selected)?;
+
+    Some((position + 1, matches.len()))
+}
+
+// Leading levels are dropped to fit, the current level is trimmed when still too long
+fn get_breadcrumb(crumbs: &[String], max_len: usize, no_unicode_symbols: bool) -> String {
+    let separator = if no_unicode_symbols {BREADCRUMB_SEPARATOR} else {UNI_BREADCRUMB_SEPARATOR};
+    let ellipsis = if no_unicode_symbols {ELLIPSIS} else {UNI_ELLIPSIS};
+    let mut start = 0;
+    let mut breadcrumb = crumbs.join(separator);
+
+    while breadcrumb.width() > max_len && start + 1 < crumbs.len() {
+        start += 1;
+        breadcrumb = format!("{}{}{}", ellipsis, separator, crumbs[start..].join(separator));
+    }
+
+    trim_string(&breadcrumb, max_len, ellipsis).1
+}
+
+// The queue item following the playing one, nothing when it is beyond the loaded queue items
+fn get_next_track(app: &App) -> Option<String> {
+    let index = app.get_now_playing_queue_index()?;
+    let two_line = &app.queue.items.as_ref()?.get(index + 1)?.two_line;
+
+    if two_line.line2.is_empty() {
+        Some(two_line.line1.to_owned())
+    } else {
+        Some(format!("{} - {}", two_line.line2, two_line.line1))
+    }
+}
+
+// Reorders text containing right-to-left scripts for display, other text is passed as is
+fn get_display_text(text: &str) -> Cow<'_, str> {
+    let is_rtl = |c: char| matches!(bidi_class(c), BidiClass::R | BidiClass::AL);
+
+    if !text.chars().any(is_rtl) {
+        return Cow::Borrowed(text);
+    }
+
+    let bidi_info = BidiInfo::new(text, None);
+    let display = bidi_info.paragraphs
+        .iter()
+        .map(|para| bidi_info.reorder_line(para, para.range.clone()))
+        .collect::<Vec<_>>()
+        .concat();
+
+    Cow::Owned(display)
+}
+
+// Scrolls text that doesn't fit the available width, one character per step
+fn get_marquee_text(text: &str, max_len: usize, step: usize) -> String {
+    if text.width() <= max_len {
+        return text.to_owned();
+    }
+
+    let looped = format!("{}{}", text, MARQUEE_GAP);
+    let start = step % looped.chars().count();
+    let mut marquee = String::new();
+    let mut len = 0;
+
+    for c in looped.chars().chain(looped.chars()).skip(start) {
+        let char_len = c.width().unwrap_or_default();
+
+        if len + char_len > max_len {
+            break;
+        }
+
+        marquee.push(c);
+        len += char_len;
+    }
+
+    marquee
+}
+
 fn get_time_string(seconds: u32) -> String {
     let hours = seconds / 3600;
     let minutes = (seconds % 3600) / 60;
@@ -462,45 +831,107 @@ fn get_queue_time_remaining(app: &App) -> Option<String> {
     }
 }
 
-fn trim_string(string: &str, trim_len: usize) -> (usize, &str) {
-    let trim = match string.char_indices().nth(trim_len) {
-        None => string,
-        Some((index, _)) => &string[..index],
-    };
+// Trims to the display width, wide characters take two columns, a cut is marked by the ellipsis
+fn trim_string(string: &str, max_len: usize, ellipsis: &str) -> (usize, String) {
+    let string_len = string.width();
+
+    if string_len <= max_len {
+        return (string_len, string.to_owned());
+    }
+
+    let ellipsis = if ellipsis.width() <= max_len {ellipsis} else {""};
+    let trim_len = max_len - ellipsis.width();
+    let mut trim = String::new();
+    let mut len = 0;
+
+    for c in string.chars() {
+        let char_len = c.width().unwrap_or_default();
 
-    (trim.chars().count(), trim)
+        if len + char_len > trim_len {
+            break;
+        }
+
+        trim.push(c);
+        len += char_len;
+    }
+
+    trim.push_str(ellipsis);
+
+    (len + ellipsis.width(), trim)
 }
 
-fn get_status_lines(zone: &Zone, style: Style) -> Vec<Line> {
-    let volume = if let Some(output) = zone.outputs.get(0) {
-        if let Some(volume) = output.volume.as_ref() {
-            match volume.scale {
-                Scale::Incremental => "Vol Incrmnt".to_owned(),
-                _ => {
-                    let is_muted = volume.is_muted.unwrap_or_default();
-
-                    if is_muted {
-                        "Vol   Muted".to_owned()
-                    } else {
-                        let volume_level = volume.value.unwrap();
-
-                        match volume.scale {
-                            Scale::Decibel => {
-                                if volume.step.unwrap() < 1.0 {
-                                    format!("Vol {:5.1}dB", volume_level)
-                                } else {
-                                    format!("Vol {:5}dB", volume_level)
-                                }
-                            }
-                            Scale::Number => format!("Vol {:7}", volume_level),
-                            _ => String::new(),
-                        }
-                    }
+fn get_volume_string(volume: Option<&Volume>) -> String {
+    if let Some(volume) = volume {
+        match volume.scale {
+            Scale::Incremental => "Vol Incrmnt".to_owned(),
+            _ => {
+                let is_muted = volume.is_muted.unwrap_or_default();
+
+                if is_muted {
+                    "Vol   Muted".to_owned()
+                } else {
+                    // A volume without its value or step can't be formatted
+                    get_volume_level_string(volume).unwrap_or_else(|| format!("Vol {:>7}", "-"))
                 }
             }
-        } else {
-            "Vol   Fixed".to_owned()
         }
+    } else {
+        "Vol   Fixed".to_owned()
+    }
+}
+
+fn get_volume_level_string(volume: &Volume) -> Option<String> {
+    let volume_level = volume.value?;
+
+    match volume.scale {
+        Scale::Decibel if volume.step? < 1.0 => Some(format!("Vol {:5.1}dB", volume_level)),
+        Scale::Decibel => Some(format!("Vol {:5}dB", volume_level)),
+        Scale::Number => Some(format!("Vol {:7}", volume_level)),
+        _ => None,
+    }
+}
+
+// Only volumes within a range that aren't muted have a bar, the others are shown as text
+fn get_volume_bar(volume: Option<&Volume>, no_unicode_symbols: bool) -> Option<String> {
+    let volume = volume?;
+
+    if volume.is_muted.unwrap_or_default() || !matches!(volume.scale, Scale::Decibel | Scale::Number) {
+        return None;
+    }
+
+    let (min, max, value) = (volume.min?, volume.max?, volume.value?);
+
+    if max <= min {
+        return None;
+    }
+
+    let fraction = ((value - min) / (max - min)).clamp(0.0, 1.0);
+    let bar = if no_unicode_symbols {
+        let filled = (fraction * VOLUME_BAR_WIDTH as f32).round() as usize;
+
+        format!("{}{}", "#".repeat(filled), "-".repeat(VOLUME_BAR_WIDTH - filled))
+    } else {
+        let eighths = (fraction * (VOLUME_BAR_WIDTH * 8) as f32).round() as usize;
+        let filled = eighths / 8;
+        let mut bar = "█".repeat(filled);
+
+        if filled < VOLUME_BAR_WIDTH {
+            bar.push(UNI_VOLUME_BAR_PARTS[eighths % 8]);
+            bar.push_str(&"░".repeat(VOLUME_BAR_WIDTH - filled - 1));
+        }
+
+        bar
+    };
+
+    Some(format!("Vol {}", bar))
+}
+
+fn get_status_lines(zone: &Zone, style: Style, volume_bar: bool, no_unicode_symbols: bool) -> Vec<Line> {
+    let volume = if let Some(output) = zone.outputs.get(0) {
+        let volume = output.volume.as_ref();
+        let bar = if volume_bar {get_volume_bar(volume, no_unicode_symbols)} else {None};
+
+        bar.unwrap_or_else(|| get_volume_string(volume))
     } else {
         String::new()
     };
@@ -539,9 +970,9 @@ fn draw_prompt_view(frame: &mut Frame, area: Rect, app: &mut App) {
 
     frame.render_widget(Clear, area);   // This clears out the background
 
-    let input = Line::from(Span::styled(app.input.as_str(), Style::default().fg(Color::Reset)));
+    let input = Line::from(Span::styled(app.input.as_str(), Style::default().fg(app.theme.text_reset)));
     let input = Paragraph::new(input)
-        .style(Style::default().fg(ROON_BRAND_COLOR))
+        .style(Style::default().fg(app.theme.brand))
         .block(block);
 
     frame.render_widget(input, area);
@@ -551,41 +982,152 @@ fn draw_prompt_view(frame: &mut Frame, area: Rect, app: &mut App) {
     frame.set_cursor(
         // Draw the cursor at the current position in the input field.
         // This position can be controlled via the left and right arrow key
-        area.x + app.cursor_position.clamp(0, max_len) as u16 + 1,
+        area.x + app.get_cursor_column().clamp(0, max_len) as u16 + 1,
         // Move one line down, from the border to the input line
         area.y + 1,
     );
 }
 
+fn draw_confirm_view(frame: &mut Frame, area: Rect, app: &App) {
+    let view = Some(&View::Confirm);
+    let area = upper_bar(area);
+    let question = app.confirm.as_ref().map(|(question, _)| *question).unwrap_or_default();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(get_border_view_style(app, view))
+        .title(Span::styled(
+            question,
+            get_text_view_style(app, view),
+        ))
+        .title_alignment(Alignment::Left);
+
+    frame.render_widget(Clear, area);   // This clears out the background
+
+    let text = Paragraph::new(Span::styled("(y/n)", Style::default().fg(app.theme.text_reset)))
+        .block(block);
+
+    frame.render_widget(text, area);
+}
+
 fn draw_zones_view(frame: &mut Frame, area: Rect, app: &mut App) {
     let view = Some(&View::Zones);
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(get_border_view_style(&app, view))
         .title(Span::styled(
-            "Zones",
+            if app.zone_transfer {"Transfer to"} else {"Zones"},
             get_text_view_style(&app, view),
         ))
         .title_alignment(Alignment::Left);
 
-    let area = bottom_right_rect(50, 50, area);
+    if !app.zone_filter.is_empty() {
+        block = block.title(
+            Title::from(
+                Span::styled(app.zone_filter.as_str(), Style::default().fg(app.theme.text_reset))
+            ).position(Position::Bottom)
+        );
+    }
+
+    if let Some(zone_sort) = app.zone_sort {
+        block = block.title(
+            Title::from(
+                Span::styled(zone_sort, Style::default().fg(app.theme.text_reset))
+            ).position(Position::Bottom).alignment(Alignment::Right)
+        );
+    }
+
+    let area = get_popup_rect(app, 50, 50, area);
     let page_lines = area.height.saturating_sub(2) as usize;  // Exclude border
 
     frame.render_widget(Clear, area);   // This clears out the background
 
-    app.zones.prepare_paging(page_lines, |_| 1);
+    app.zones.area = area;
+
+    let expanded_zones = &app.expanded_zones;
+
+    app.zones.prepare_paging(page_lines, |(end_point, _, _, outputs, _)| {
+        match end_point {
+            EndPoint::Zone(zone_id) if expanded_zones.contains(zone_id) => 1 + outputs.len(),
+            _ => 1,
+        }
+    });
 
     if let Some(zones) = app.zones.items.as_ref() {
+        let playing_symbol = if app.no_unicode_symbols {PLAYING_SYMBOL} else {UNI_PLAYING_SYMBOL};
+        let standby_symbol = if app.no_unicode_symbols {STANDBY_SYMBOL} else {UNI_STANDBY_SYMBOL};
         let items: Vec<ListItem> = zones
             .iter()
-            .map(|(end_point, name)| {
+            .map(|(end_point, name, state, outputs, standby)| {
+                // Keep the names aligned, only zones have a state
+                let state_symbol = match (state, standby) {
+                    (_, Some((_, true))) => standby_symbol,
+                    (Some(State::Playing), _) => playing_symbol,
+                    _ => " ",
+                };
                 let name = match end_point {
-                    EndPoint::Preset(_) => format!("[{}]", name),
-                    EndPoint::Output(_) => format!("<{}>", name),
-                    EndPoint::Zone(_) => name.to_owned(),
+                    EndPoint::Preset(_) => format!("{} [{}]", state_symbol, name),
+                    EndPoint::Output(_) => format!("{} <{}>", state_symbol, name),
+                    EndPoint::Zone(_) => format!("{} {}", state_symbol, name),
                 };
+                let style = get_text_view_style(&app, view);
+                let mut lines = vec![Line::from(Span::styled(name, style))];
+
+                if let EndPoint::Zone(zone_id) = end_point {
+                    if app.expanded_zones.contains(zone_id) {
+                        // Members of the group, indented beneath it
+                        for output in outputs {
+                            lines.push(Line::from(Span::styled(format!("    {}", output), style)));
+                        }
+                    }
+                }
+
+                ListItem::new(lines).style(Style::default())
+            })
+            .collect();
+
+        // Create a List from all list items and highlight the currently selected one
+        let highlight_symbol = if app.no_unicode_symbols {HIGHLIGHT_SYMBOL} else {UNI_HIGHLIGHT_SYMBOL};
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL))
+            .highlight_style(
+                Style::default()
+                    .bg(app.theme.highlight_bg)
+                    .add_modifier(Modifier::BOLD)
+            )
+            .highlight_symbol(highlight_symbol);
+
+        // We can now render the item list
+        frame.render_stateful_widget(list, area, &mut app.zones.state);
+    }
+
+    frame.render_widget(block, area);
+}
+
+fn draw_bookmarks_view(frame: &mut Frame, area: Rect, app: &mut App) {
+    let view = Some(&View::Bookmarks);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(get_border_view_style(&app, view))
+        .title(Span::styled(
+            "Bookmarks",
+            get_text_view_style(&app, view),
+        ))
+        .title_alignment(Alignment::Left);
+
+    let area = get_popup_rect(app, 50, 50, area);
+    let page_lines = area.height.saturating_sub(2) as usize;  // Exclude border
+
+    frame.render_widget(Clear, area);   // This clears out the background
+
+    app.bookmarks.area = area;
+    app.bookmarks.prepare_paging(page_lines, |_| 1);
+
+    if let Some(bookmarks) = app.bookmarks.items.as_ref() {
+        let items: Vec<ListItem> = bookmarks
+            .iter()
+            .map(|name| {
                 let line = Span::styled(
-                    name,
+                    name.as_str(),
                     get_text_view_style(&app, view));
                 ListItem::new(Line::from(line)).style(Style::default())
             })
@@ -597,13 +1139,138 @@ fn draw_zones_view(frame: &mut Frame, area: Rect, app: &mut App) {
             .block(Block::default().borders(Borders::ALL))
             .highlight_style(
                 Style::default()
-                    .bg(ROON_BRAND_COLOR)
+                    .bg(app.theme.highlight_bg)
                     .add_modifier(Modifier::BOLD)
             )
             .highlight_symbol(highlight_symbol);
 
-        // We can now render the item list
-        frame.render_stateful_widget(list, area, &mut app.zones.state);
+        frame.render_stateful_widget(list, area, &mut app.bookmarks.state);
+    }
+
+    frame.render_widget(block, area);
+}
+
+fn draw_zone_settings_view(frame: &mut Frame, area: Rect, app: &mut App) {
+    let view = Some(&View::ZoneSettings);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(get_border_view_style(&app, view))
+        .title(Span::styled(
+            "Zone Settings",
+            get_text_view_style(&app, view),
+        ))
+        .title_alignment(Alignment::Left);
+
+    let area = get_popup_rect(app, 30, 100, area);
+    let page_lines = area.height.saturating_sub(2) as usize;  // Exclude border
+
+    frame.render_widget(Clear, area);   // This clears out the background
+
+    app.zone_settings.area = area;
+    app.zone_settings.prepare_paging(page_lines, |_| 1);
+
+    if let Some(zone_settings) = app.zone_settings.items.as_ref() {
+        let checked_symbol = if app.no_unicode_symbols {CHECKED_SYMBOL} else {UNI_CHECKED_SYMBOL};
+        let unchecked_symbol = if app.no_unicode_symbols {UNCHECKED_SYMBOL} else {UNI_UNCHECKED_SYMBOL};
+        let items: Vec<ListItem> = zone_settings
+            .iter()
+            .map(|(name, enabled)| {
+                let state = if *enabled {checked_symbol} else {unchecked_symbol};
+                // Setting names are in snake case, e.g. auto_radio
+                let name = name.replace('_', " ");
+                let line = Span::styled(
+                    format!("{}  {}", state, name),
+                    get_text_view_style(&app, view));
+                ListItem::new(Line::from(line)).style(Style::default())
+            })
+            .collect();
+
+        // Create a List from all list items and highlight the currently selected one
+        let highlight_symbol = if app.no_unicode_symbols {HIGHLIGHT_SYMBOL} else {UNI_HIGHLIGHT_SYMBOL};
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL))
+            .highlight_style(
+                Style::default()
+                    .bg(app.theme.highlight_bg)
+                    .add_modifier(Modifier::BOLD)
+            )
+            .highlight_symbol(highlight_symbol);
+
+        frame.render_stateful_widget(list, area, &mut app.zone_settings.state);
+    }
+
+    frame.render_widget(block, area);
+}
+
+fn draw_overview_view(frame: &mut Frame, area: Rect, app: &mut App) {
+    let view = Some(&View::Overview);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(get_border_view_style(app, view))
+        .title(Span::styled(
+            "Zones Overview",
+            get_text_view_style(app, view),
+        ))
+        .title_alignment(Alignment::Left);
+
+    let area = get_popup_rect(app, 50, 50, area);
+    let page_lines = area.height.saturating_sub(2) as usize;  // Exclude border
+
+    frame.render_widget(Clear, area);   // This clears out the background
+
+    app.zones_overview.area = area;
+    app.zones_overview.prepare_paging(page_lines, |_| 2);
+
+    if let Some(summaries) = app.zones_overview.items.as_ref() {
+        let playing_symbol = if app.no_unicode_symbols {PLAYING_SYMBOL} else {UNI_PLAYING_SYMBOL};
+        let style = get_text_view_style(app, view);
+        let secondary_style = get_secondary_style(app);
+        let items: Vec<ListItem> = summaries
+            .iter()
+            .map(|summary| {
+                let state = match summary.state {
+                    State::Loading => "Loading",
+                    State::Paused => "Paused",
+                    State::Playing => "Playing",
+                    State::Stopped => "Stopped",
+                };
+                let state_symbol = if summary.state == State::Playing {playing_symbol} else {" "};
+                let position = summary.seek_position.map(|seek_position| get_time_string(seek_position.max(0) as u32));
+                let state = match (position, summary.length) {
+                    (Some(position), Some(length)) => format!("{} {}/{}", state, position, get_time_string(length)),
+                    (Some(position), None) => format!("{} {}", state, position),
+                    _ => state.to_owned(),
+                };
+                let lines = vec![
+                    Line::from(Span::styled(format!("{} {} | {}", state_symbol, summary.display_name, state), style)),
+                    get_secondary_line(&summary.title, secondary_style),
+                ];
+
+                ListItem::new(lines).style(Style::default())
+            })
+            .collect();
+
+        // Create a List from all list items and highlight the currently selected one
+        let highlight_symbol = if app.no_unicode_symbols {HIGHLIGHT_SYMBOL} else {UNI_HIGHLIGHT_SYMBOL};
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL))
+            .highlight_style(
+                Style::default()
+                    .bg(app.theme.highlight_bg)
+                    .add_modifier(Modifier::BOLD)
+            )
+            .highlight_symbol(highlight_symbol);
+
+        frame.render_stateful_widget(list, area, &mut app.zones_overview.state);
+
+        if summaries.is_empty() {
+            let inner = area.inner(&Margin { horizontal: 1, vertical: 1 });
+            let line = Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner };
+            let text = Paragraph::new(Span::styled("(nothing playing)", Style::default().fg(app.theme.gray)))
+                .alignment(Alignment::Center);
+
+            frame.render_widget(text, line);
+        }
     }
 
     frame.render_widget(block, area);
@@ -618,7 +1285,7 @@ fn draw_grouping_view(frame: &mut Frame, area: Rect, app: &mut App) -> Option<()
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(get_border_view_style(app, Some(&view)));
-    let area = bottom_right_rect(50, 50, area);
+    let area = get_popup_rect(app, 50, 50, area);
     let vchunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
@@ -646,10 +1313,10 @@ fn draw_grouping_view(frame: &mut Frame, area: Rect, app: &mut App) -> Option<()
 
         let input = vec![
             Line::from(""),                 // Hidden underneath border
-            Line::from(Span::styled(app.input.as_str(), Style::default().fg(Color::Reset).add_modifier(Modifier::BOLD)))
+            Line::from(Span::styled(app.input.as_str(), Style::default().fg(app.theme.text_reset).add_modifier(Modifier::BOLD)))
         ];
         let input = Paragraph::new(input)
-            .style(Style::default().fg(ROON_BRAND_COLOR));
+            .style(Style::default().fg(app.theme.brand));
 
         frame.render_widget(input, vchunks[0]);
 
@@ -658,7 +1325,7 @@ fn draw_grouping_view(frame: &mut Frame, area: Rect, app: &mut App) -> Option<()
         frame.set_cursor(
             // Draw the cursor at the current position in the input field.
             // This position can be controlled via the left and right arrow key
-            vchunks[0].x + app.cursor_position.clamp(0, max_len) as u16,
+            vchunks[0].x + app.get_cursor_column().clamp(0, max_len) as u16,
             // Move one line down, from the border to the input line
             vchunks[0].y + 1,
         );
@@ -675,10 +1342,11 @@ fn draw_grouping_view(frame: &mut Frame, area: Rect, app: &mut App) -> Option<()
         };
         let zone_name = vec![
             Line::from(""),                 // Hidden underneath border
-            Line::from(Span::styled(zone_name, Style::default().fg(Color::Reset).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(zone_name, Style::default().fg(app.theme.text_reset).add_modifier(Modifier::BOLD))),
         ];
         let page_lines = list_area.height as usize;
 
+        app.grouping.area = list_area;
         app.grouping.prepare_paging(page_lines, |_| 1);
 
         frame.render_widget(Paragraph::new(zone_name), vchunks[0]);
@@ -689,10 +1357,14 @@ fn draw_grouping_view(frame: &mut Frame, area: Rect, app: &mut App) -> Option<()
     let unchecked_symbol = if app.no_unicode_symbols {UNCHECKED_SYMBOL} else {UNI_UNCHECKED_SYMBOL};
     let items: Vec<ListItem> = grouping
         .iter()
-        .map(|(_, name, included)| {
+        .map(|(output_id, name, included)| {
             let state = if *included {checked_symbol} else {unchecked_symbol};
+            let output = format!("{}  {}", state, name);
+            let volume = get_volume_string(app.output_volumes.get(output_id));
+            let pad_len = (list_area.width as usize).saturating_sub(output.width() + volume.width() + 1);
+            let pad: String = (0..pad_len).map(|_| ' ').collect();
             let line = Span::styled(
-                format!("{}  {}", state, name),
+                format!("{}{} {}", output, pad, volume),
                 get_text_view_style(&app, Some(&View::Grouping)));
 
             ListItem::new(Line::from(line)).style(Style::default())
@@ -704,7 +1376,7 @@ fn draw_grouping_view(frame: &mut Frame, area: Rect, app: &mut App) -> Option<()
         .block(Block::default())
         .highlight_style(
             Style::default()
-                .bg(ROON_BRAND_COLOR)
+                .bg(app.theme.highlight_bg)
                 .add_modifier(Modifier::BOLD)
         );
 
@@ -740,23 +1412,15 @@ fn draw_help_view(frame: &mut Frame, area: Rect, app: &mut App) {
             Constraint::Percentage(33)].as_ref())
         .split(chunk[0]);
     let max_entries: usize = (hor_chunks[0].height as usize).saturating_sub(2);
-    let text = [
-        "__Global__",
-        "Tab     Next view",
-        "Sh-Tab  Previous view",
-        "Ctrl-z  Select zone",
-        "Ctrl-g  Group zones",
-        "Ctrl-Sp Play/Pause",
-        "Ctrl-p  Play/Pause",
-        "Ctrl-e  Pause at end",
-        "Ctrl-Up Volume up",
-        "Ctrl-Dn Volume down",
-        "Ctrl-Ri Next track",
-        "Ctrl-Le Previous track",
-        "Ctrl-q  Queue mode",
-        "Ctrl-a  Append queue",
-        "Ctrl-h  This help page",
-        "Ctrl-c  Quit",
+    let mut global_text = vec!["__Global__".to_owned()];
+
+    for action in Action::ALL {
+        for key_name in app.keymap.get_key_names(action) {
+            global_text.push(format!("{:<7} {}", key_name, action.description()));
+        }
+    }
+
+    let static_text = [
         "",
         "__List Controls__",
         "Up      Move up",
@@ -770,44 +1434,94 @@ fn draw_help_view(frame: &mut Frame, area: Rect, app: &mut App) {
         "Enter   Select",
         "Esc     Move level up",
         "Ctrl-Hm Browse home",
+        "Ctrl-d  Add bookmark",
+        "Ctrl-b  Bookmarks",
+        "Ctrl-y  Copy item title",
         "F5      Refresh",
-        "a..z    Char jump",
+        "a..z    Fuzzy jump",
         "Backsp  Prev char jump",
+        "Home    Top, ends jump",
+        "Alt-n/N Next/prev match",
+        "/       Toggle filter",
+        "P       Play now",
+        "A       Add next",
+        "Q       Add to queue",
         "",
         "__Queue View__",
         "Enter   Play from here",
+        "n       Now playing",
+        "e       Export to file",
         "",
         "__Now Playing View__",
         "m       Mute",
         "u       Unmute",
         "+       Volume up",
         "-       Volume down",
+        "v       Set volume",
+        "f       Toggle full screen",
+        "t       Transfer playback",
+        "o       Zone settings",
+        "c       Copy track info",
+        "d       Total/remaining",
+        "z       Sleep timer",
+        "a       Alarm",
         "r       Toggle Repeat",
         "s       Toggle shuffle",
+        "Le/Ri   Seek -/+10s",
         "",
         "__Zone Select Popup__",
         "Enter   Select zone",
         "Esc     Back to view",
         "Delete  Delete preset",
+        "F2      Edit preset",
+        "Ri/Le   Show/hide group",
+        "a..z    Filter zones",
+        "Ctrl-o  Toggle standby",
+        "Ctrl-s  Sort order",
+        "",
+        "__Bookmarks Popup__",
+        "Enter   Go to bookmark",
+        "Esc     Back to view",
+        "Delete  Delete bookmark",
         "",
         "__Zone Grouping Popup__",
         "Space   Toggle output",
         "Enter   Activate group",
         "s       Save as preset",
+        "+/-     Output volume",
+        "m/u     Mute/Unmute output",
+        "Esc     Back to view",
+        "",
+        "__Zone Settings Popup__",
+        "Space   Toggle setting",
+        "Esc     Back to view",
+        "",
+        "__Zones Overview Popup__",
+        "Enter   Select zone",
         "Esc     Back to view",
         "",
+        "__Confirmation__",
+        "y       Confirm",
+        "n/Esc   Cancel",
+        "",
         "__Text Input__",
         "Enter   Confirm input",
         "Esc     Cancel input",
+        "Up/Down Search history",
     ];
 
+    let text = global_text.iter()
+        .map(|line| line.as_str())
+        .chain(static_text)
+        .collect::<Vec<_>>();
+
     frame.render_widget(Clear, chunk[0]);   // This clears out the background
 
     for column in 0..hor_chunks.len() {
         let start = column * max_entries;
         let end = (start + max_entries).clamp(start, text.len());
 
-        frame.render_widget(create_paragraph(&text[start..end]), hor_chunks[column]);
+        frame.render_widget(create_paragraph(&text[start..end], app.theme.text_reset), hor_chunks[column]);
 
         if end == text.len() {
             break;
@@ -817,7 +1531,7 @@ fn draw_help_view(frame: &mut Frame, area: Rect, app: &mut App) {
     frame.render_widget(block, chunk[0]);
 }
 
-fn create_paragraph<'a>(text: &'a[&str]) -> Paragraph<'a> {
+fn create_paragraph<'a>(text: &'a[&str], color: Color) -> Paragraph<'a> {
     let block = Block::default()
         .padding(Padding {
             left: 1,
@@ -825,7 +1539,7 @@ fn create_paragraph<'a>(text: &'a[&str]) -> Paragraph<'a> {
             top: 1,
             bottom: 0,
         });
-    let style = Style::default().fg(Color::Reset);
+    let style = Style::default().fg(color);
     let mut lines = Vec::new();
 
     for line in text {
@@ -848,49 +1562,62 @@ fn get_border_view_style(app: &App, view: Option<&View>) -> Style {
     if let Some(selected_view) = app.get_selected_view() {
         if let Some(view) = view {
             if *selected_view == *view {
-                style = style.fg(ROON_BRAND_COLOR);
+                style = style.fg(app.theme.brand);
             }
         }
     } else if view.is_none() {
-        style = style.fg(ROON_BRAND_COLOR);
+        style = style.fg(app.theme.brand);
     } else {
-        style = style.fg(CUSTOM_GRAY);
+        style = style.fg(app.theme.gray);
     }
 
     style
 }
 
+// The subtitle of a Browse item, or the second line of a Queue item
+fn get_secondary_style(app: &App) -> Style {
+    if app.get_selected_view().is_some() {
+        Style::default().add_modifier(Modifier::ITALIC)
+    } else {
+        Style::default().fg(app.theme.gray).add_modifier(Modifier::ITALIC)
+    }
+}
+
+fn get_secondary_line(text: &str, style: Style) -> Line<'static> {
+    Line::from(Span::styled(format!("  {}", get_display_text(text)), style))
+}
+
 fn get_text_view_style(app: &App, view: Option<&View>) -> Style {
     let mut style = Style::default();
 
     if let Some(selected_view) = app.get_selected_view() {
         if let Some(view) = view {
             if *selected_view == *view {
-                style = style.fg(Color::Reset).add_modifier(Modifier::BOLD);
+                style = style.fg(app.theme.text_reset).add_modifier(Modifier::BOLD);
             }
         }
     } else if view.is_none() {
-        style = style.fg(Color::Reset).add_modifier(Modifier::BOLD);
+        style = style.fg(app.theme.text_reset).add_modifier(Modifier::BOLD);
     } else {
-        style = style.fg(CUSTOM_GRAY);
+        style = style.fg(app.theme.gray);
     }
 
     style
 }
 
 fn get_gauge_view_style(app: &App, view: Option<&View>) -> Style {
-    let mut style = Style::default().bg(Color::Rgb(0x30, 0x30, 0x30));
+    let mut style = Style::default().bg(app.theme.gauge_empty_bg);
 
     if let Some(selected_view) = app.get_selected_view() {
         if let Some(view) = view {
             if *selected_view == *view {
-                style = style.fg(ROON_BRAND_COLOR);
+                style = style.fg(app.theme.brand);
             } else {
-                style = style.fg(CUSTOM_GRAY);
+                style = style.fg(app.theme.gray);
             }
         }
     } else if view.is_some() {
-        style = style.fg(Color::Rgb(0x30, 0x30, 0x30));
+        style = style.fg(app.theme.gauge_bg);
     }
 
     style
@@ -909,6 +1636,13 @@ fn upper_bar(rect: Rect) -> Rect {
         .split(rect)[0]
 }
 
+// Popups take the full width when the views are stacked
+fn get_popup_rect(app: &App, percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
+    let percent_x = if app.vertical_layout {100} else {percent_x};
+
+    bottom_right_rect(percent_x, percent_y, rect)
+}
+
 fn bottom_right_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -932,3 +1666,45 @@ fn bottom_right_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
         )
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_trim(string: &str, max_len: usize, ellipsis: &str, expected: &str) {
+        let (len, trim) = trim_string(string, max_len, ellipsis);
+
+        assert_eq!(trim, expected);
+        assert_eq!(len, trim.width());
+        assert!(len <= max_len);
+    }
+
+    #[test]
+    fn trim_ascii() {
+        assert_trim("Blue Train", 10, ELLIPSIS, "Blue Train");
+        assert_trim("Blue Train", 9, ELLIPSIS, "Blue T...");
+        assert_trim("Blue Train", 9, UNI_ELLIPSIS, "Blue Tra\u{2026}");
+        // Without room for the ellipsis the text is cut off
+        assert_trim("Blue Train", 2, ELLIPSIS, "Bl");
+        assert_trim("Blue Train", 0, ELLIPSIS, "");
+    }
+
+    #[test]
+    fn trim_double_width() {
+        // Each character takes two columns
+        assert_trim("日本語のタイトル", 16, UNI_ELLIPSIS, "日本語のタイトル");
+        assert_trim("日本語のタイトル", 15, UNI_ELLIPSIS, "日本語のタイト\u{2026}");
+        // Half a character doesn't fit, the line ends a column short
+        assert_trim("日本語のタイトル", 8, UNI_ELLIPSIS, "日本語\u{2026}");
+        assert_trim("日本語のタイトル", 9, ELLIPSIS, "日本語...");
+        assert_trim("Mix 日本", 6, UNI_ELLIPSIS, "Mix \u{2026}");
+    }
+
+    #[test]
+    fn trim_emoji() {
+        assert_trim("\u{1F3B5}\u{1F3B6} Music", 10, UNI_ELLIPSIS, "\u{1F3B5}\u{1F3B6} Music");
+        assert_trim("\u{1F3B5}\u{1F3B6} Music", 9, UNI_ELLIPSIS, "\u{1F3B5}\u{1F3B6} Mus\u{2026}");
+        assert_trim("\u{1F3B5}\u{1F3B6} Music", 5, UNI_ELLIPSIS, "\u{1F3B5}\u{1F3B6}\u{2026}");
+        assert_trim("\u{1F3B5}\u{1F3B6} Music", 4, ELLIPSIS, "...");
+    }
+}
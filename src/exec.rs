@@ -0,0 +1,192 @@
+use eyre::{bail, Result};
+use roon_api::transport::{volume, Control, Seek, State, Zone};
+use serde::Serialize;
+use tokio::{sync::mpsc, time::{timeout, timeout_at, Duration, Instant}};
+
+use crate::io::{EndPoint, IoEvent, ZoneEntry};
+
+// Pairing with a new Server requires enabling the extension in Roon first
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const SETTLE_TIME: Duration = Duration::from_millis(1000);
+
+#[derive(Serialize)]
+struct Status<'a> {
+    zone: &'a str,
+    state: &'a State,
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    volume: Option<f32>,
+    muted: bool,
+    elapsed: Option<i64>,
+    length: Option<u32>,
+}
+
+pub enum Command {
+    Event(Box<IoEvent>),
+    SelectZone(String),
+}
+
+pub fn parse_command(command: &str) -> Option<Command> {
+    let command = command.trim();
+    let (name, arg) = match command.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (command, ""),
+    };
+    let name = name.to_ascii_lowercase().replace('_', "-");
+
+    let event = match (name.as_str(), arg) {
+        ("play-pause", "") => IoEvent::Control(Control::PlayPause),
+        ("play", "") => IoEvent::Control(Control::Play),
+        ("pause", "") => IoEvent::Control(Control::Pause),
+        ("stop", "") => IoEvent::Control(Control::Stop),
+        ("next", "") => IoEvent::Control(Control::Next),
+        ("previous" | "prev", "") => IoEvent::Control(Control::Previous),
+        ("mute", "") => IoEvent::Mute(volume::Mute::Mute),
+        ("unmute", "") => IoEvent::Mute(volume::Mute::Unmute),
+        ("repeat", "") => IoEvent::Repeat,
+        ("shuffle", "") => IoEvent::Shuffle,
+        ("volume", arg) if is_relative(arg) => IoEvent::ChangeVolume(arg.parse().ok()?),
+        ("volume", arg) => IoEvent::SetVolumeAbsolute(arg.parse().ok()?),
+        ("seek", arg) if is_relative(arg) => IoEvent::Seek(Seek::Relative, arg.parse().ok()?),
+        ("seek", arg) => IoEvent::Seek(Seek::Absolute, arg.parse().ok()?),
+        ("select-zone", arg) if !arg.is_empty() => {
+            return Some(Command::SelectZone(arg.trim_matches('"').to_owned()));
+        }
+        _ => return None,
+    };
+
+    Some(Command::Event(Box::new(event)))
+}
+
+fn is_relative(arg: &str) -> bool {
+    arg.starts_with('+') || arg.starts_with('-')
+}
+
+pub fn find_zone_id(zones: &[ZoneEntry], name: &str) -> Option<String> {
+    zones.iter().find_map(|(end_point, display_name, _, _, _)| {
+        match end_point {
+            EndPoint::Zone(zone_id) if display_name.eq_ignore_ascii_case(name) => Some(zone_id.to_owned()),
+            _ => None,
+        }
+    })
+}
+
+// Performs a single command without the UI and prints the resulting zone state as JSON
+pub async fn run(command: &str, to_roon: mpsc::Sender<IoEvent>, mut from_roon: mpsc::Receiver<IoEvent>) -> Result<()> {
+    let mut command = match parse_command(command) {
+        Some(command) => Some(command),
+        None => bail!("Unknown command: {}", command),
+    };
+    let mut zone: Option<Zone> = None;
+
+    // Wait for the initial state, the command is sent as soon as its zone is known
+    while command.is_some() {
+        let io_event = match timeout(CONNECT_TIMEOUT, from_roon.recv()).await {
+            Ok(Some(io_event)) => io_event,
+            _ => bail!("No zone state received from the Roon Server"),
+        };
+
+        match io_event {
+            IoEvent::Zones(zones) => {
+                match command.take() {
+                    Some(Command::SelectZone(name)) => {
+                        let zone_id = match find_zone_id(&zones, &name) {
+                            Some(zone_id) => zone_id,
+                            None => bail!("Zone not found: {}", name),
+                        };
+
+                        to_roon.send(IoEvent::ZoneSelected(EndPoint::Zone(zone_id))).await?;
+                    }
+                    // The state of the selected zone is sent before the zone list
+                    Some(Command::Event(_)) if zone.is_none() => bail!("No zone selected, use select-zone first"),
+                    pending => command = pending,
+                }
+            }
+            IoEvent::ZoneChanged(changed) => {
+                zone = Some(changed);
+
+                match command.take() {
+                    Some(Command::Event(io_event)) => to_roon.send(*io_event).await?,
+                    pending => command = pending,
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Collect the changes caused by the command until the zone state settles
+    let mut deadline = Instant::now() + SETTLE_TIME;
+
+    while let Ok(Some(io_event)) = timeout_at(deadline, from_roon.recv()).await {
+        if let IoEvent::ZoneChanged(changed) = io_event {
+            zone = Some(changed);
+            deadline = Instant::now() + SETTLE_TIME;
+        }
+    }
+
+    match zone {
+        Some(zone) => println!("{}", serde_json::to_string(&zone)?),
+        None => bail!("No zone selected"),
+    }
+
+    Ok(())
+}
+
+// Prints the now playing status of the selected zone as a single JSON line,
+// keeps printing a line on each change when watching
+pub async fn status(mut from_roon: mpsc::Receiver<IoEvent>, watch: bool) -> Result<()> {
+    let mut zone: Option<Zone> = None;
+
+    loop {
+        let io_event = if zone.is_none() {
+            match timeout(CONNECT_TIMEOUT, from_roon.recv()).await {
+                Ok(Some(io_event)) => io_event,
+                _ => bail!("No zone state received from the Roon Server"),
+            }
+        } else {
+            match from_roon.recv().await {
+                Some(io_event) => io_event,
+                None => return Ok(()),
+            }
+        };
+
+        match io_event {
+            IoEvent::Zones(_) if zone.is_none() => bail!("No zone selected, use select-zone first"),
+            IoEvent::ZoneChanged(changed) => zone = Some(changed),
+            IoEvent::ZoneSeek(seek) => {
+                match zone.as_mut().and_then(|zone| zone.now_playing.as_mut()) {
+                    Some(now_playing) => now_playing.seek_position = seek.seek_position,
+                    None => continue,
+                }
+            }
+            _ => continue,
+        }
+
+        if let Some(zone) = zone.as_ref() {
+            println!("{}", get_status_json(zone)?);
+
+            if !watch {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn get_status_json(zone: &Zone) -> serde_json::Result<String> {
+    let now_playing = zone.now_playing.as_ref();
+    let volume = zone.outputs.first().and_then(|output| output.volume.as_ref());
+    let status = Status {
+        zone: &zone.display_name,
+        state: &zone.state,
+        title: now_playing.map(|now_playing| now_playing.three_line.line1.as_str()),
+        artist: now_playing.map(|now_playing| now_playing.three_line.line2.as_str()),
+        album: now_playing.map(|now_playing| now_playing.three_line.line3.as_str()),
+        volume: volume.and_then(|volume| volume.value),
+        muted: volume.and_then(|volume| volume.is_muted).unwrap_or_default(),
+        elapsed: now_playing.and_then(|now_playing| now_playing.seek_position),
+        length: now_playing.and_then(|now_playing| now_playing.length),
+    };
+
+    serde_json::to_string(&status)
+}
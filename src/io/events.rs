@@ -1,4 +1,6 @@
-use crossterm::event::{self, KeyEventKind, KeyModifiers, KeyCode};
+use crossterm::{execute, event};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 use crate::io::IoEvent;
@@ -6,21 +8,53 @@ use crate::io::IoEvent;
 pub struct Events;
 
 impl Events {
-    pub fn start(to_app: mpsc::Sender<IoEvent>) {
+    pub fn start(to_app: mpsc::Sender<IoEvent>, tick_rate: Duration, ticking: Arc<AtomicBool>) {
+        // Receive pasted text as a whole instead of as individual key events
+        let _ = execute!(std::io::stdout(), event::EnableBracketedPaste);
+
         tokio::spawn(async move {
-            loop {
+            let mut last_tick = Instant::now();
+
+            // Poll with a timeout to detect that the app has quit, the quit key can be remapped
+            while !to_app.is_closed() {
+                let timeout = tick_rate
+                    .saturating_sub(last_tick.elapsed())
+                    .min(Duration::from_millis(100));
+
+                if last_tick.elapsed() >= tick_rate {
+                    last_tick = Instant::now();
+
+                    // Ticks are only needed for animation while playing
+                    if ticking.load(Ordering::Relaxed) && to_app.send(IoEvent::Tick).await.is_err() {
+                        break;
+                    }
+                }
+
+                if !event::poll(timeout).unwrap() {
+                    continue;
+                }
+
                 match event::read().unwrap() {
                     event::Event::Key(key) => {
-                        to_app.send(IoEvent::Input(key)).await.unwrap();
-
-                        if key.kind == KeyEventKind::Press
-                            && key.modifiers == KeyModifiers::CONTROL
-                            && key.code == KeyCode::Char('c')
-                        {
+                        if to_app.send(IoEvent::Input(key)).await.is_err() {
+                            break;
+                        }
+                    }
+                    event::Event::Mouse(mouse) => {
+                        if to_app.send(IoEvent::Mouse(mouse)).await.is_err() {
+                            break;
+                        }
+                    }
+                    event::Event::Paste(text) => {
+                        if to_app.send(IoEvent::Paste(text)).await.is_err() {
+                            break;
+                        }
+                    }
+                    event::Event::Resize(_, _) => {
+                        if to_app.send(IoEvent::Redraw).await.is_err() {
                             break;
                         }
                     }
-                    event::Event::Resize(_, _) => to_app.send(IoEvent::Redraw).await.unwrap(),
                     _ => (),
                 }
             }
@@ -1,9 +1,12 @@
-use crossterm::event::KeyEvent;
-use roon_api::{browse, transport::{QueueItem, QueueChange, Zone, ZoneSeek, volume, Control}};
+use crossterm::event::{KeyEvent, MouseEvent};
+use roon_api::{browse, transport::{QueueItem, QueueChange, State, Zone, ZoneSeek, volume, Control, Seek}};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Instant};
 
 pub mod events;
 pub mod roon;
+#[cfg(unix)]
+pub mod socket;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -14,6 +17,30 @@ pub enum QueueMode {
     RandomTrack = 3,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueueAction {
+    PlayNow,
+    AddNext,
+    Queue,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneSort {
+    #[default] Alphabetical = 0,
+    RecentlyUsed = 1,
+    PlayingFirst = 2,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ConnectionState {
+    #[default] Discovering,
+    Connecting(String),
+    Found(String),
+    Lost(String),
+    Retrying(u64),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum EndPoint {
     Zone(String),
@@ -21,44 +48,150 @@ pub enum EndPoint {
     Preset(String),
 }
 
+// Zone list entry: end point, display name, zone state, output names of a grouped zone
+// and the output supporting standby together with its standby status
+pub type ZoneEntry = (EndPoint, String, Option<State>, Vec<String>, Option<(String, bool)>);
+
+// A zone with a track, as listed in the zones overview
+#[derive(Clone, Debug)]
+pub struct ZoneSummary {
+    pub zone_id: String,
+    pub display_name: String,
+    pub state: State,
+    pub title: String,
+    pub seek_position: Option<i64>,
+    pub length: Option<u32>,
+}
+
 #[derive(Debug)]
 pub enum IoEvent {
     Input(KeyEvent),
+    Paste(String),
+    Mouse(MouseEvent),
     Redraw,
-    CoreName(Option<String>),
+    Tick,
+    Connection(ConnectionState),
+    Reconnect,
+    Message(&'static str),
+    Status(String),
     BrowseTitle(String),
+    BrowseBreadcrumb(Vec<String>),
     BrowseList(usize, Vec<browse::Item>),
+    BrowseMessage(String, bool),
+    BrowseLoading(bool),
     BrowseSelected(Option<String>),
     BrowseBack,
     BrowseRefresh,
     BrowseHome,
     BrowseInput(String),
-    QueueList(Vec<QueueItem>),
+    BrowseSearch,
+    BrowseSearchPrompt(browse::Item),
+    BrowsePlayAction(browse::Item, QueueAction),
+    Bookmarks(Vec<String>),
+    BookmarkAdd(String),
+    BookmarkSelect(String),
+    BookmarkDelete(String),
+    // The queue items and whether they are the complete queue
+    QueueList(Vec<QueueItem>, bool),
     QueueListChanges(Vec<QueueChange>),
     QueueListLast(Option<QueueItem>),
+    QueueLoadMore(usize),
+    QueueResync,
+    QueueLoading(bool),
     QueueSelected(u32),
     QueueClear,
     QueueModeNext,
     QueueModeAppend,
     QueueModeCurrent(QueueMode),
-    Zones(Vec<(EndPoint, String)>),
+    Zones(Vec<ZoneEntry>),
     ZoneSelect,
+    ZoneSortNext,
+    ZoneSortCurrent(ZoneSort),
     ZoneSelected(EndPoint),
+    ZoneTransfer(String),
+    ToggleStandby(String),
     ZoneChanged(Zone),
     ZoneRemoved(String),
     ZoneSeek(ZoneSeek),
+    ZonesOverviewReq,
+    ZonesOverview(Vec<ZoneSummary>),
     ZoneGroupReq,
     ZoneGrouping(Option<Vec<(String, String, bool)>>),
     ZoneGrouped(Vec<String>),
     ZoneSavePreset(String, Vec<String>),
     ZoneDeletePreset(String),
+    ZoneEditPreset(String),
     ZoneMatchPreset(Vec<String>),
     ZonePresetMatched(Option<String>),
     Mute(volume::Mute),
     ChangeVolume(i32),
+    SetVolumeAbsolute(f32),
+    ChangeOutputVolume(String, i32),
+    MuteOutput(String, volume::Mute),
+    OutputVolumes(HashMap<String, volume::Volume>),
     Control(Control),
+    Seek(Seek, i32),
     Repeat,
     Shuffle,
+    ZoneSettingToggle(String),
+    RemainingTimeToggle,
+    RemainingTime(bool),
+    SplitChanged(u16),
+    Split(u16),
     PauseOnTrackEndReq,
     PauseOnTrackEndActive(bool),
+    SleepTimer(u32),
+    SleepTimerActive(Option<Instant>),
+    Alarm(Option<String>),
+    AlarmActive(Option<String>),
+    ImageData(Vec<u8>),
+}
+
+impl IoEvent {
+    // Requests that change the playback, the queue or the zones
+    pub fn is_control(&self) -> bool {
+        matches!(self,
+            IoEvent::BrowsePlayAction(_, _)
+            | IoEvent::QueueSelected(_)
+            | IoEvent::QueueClear
+            | IoEvent::QueueModeNext
+            | IoEvent::QueueModeAppend
+            | IoEvent::ZoneTransfer(_)
+            // Selecting an output ungroups it, selecting a preset regroups its outputs
+            | IoEvent::ZoneSelected(EndPoint::Output(_) | EndPoint::Preset(_))
+            | IoEvent::ToggleStandby(_)
+            | IoEvent::ZoneGrouped(_)
+            | IoEvent::ZoneSavePreset(_, _)
+            | IoEvent::ZoneDeletePreset(_)
+            | IoEvent::Mute(_)
+            | IoEvent::ChangeVolume(_)
+            | IoEvent::SetVolumeAbsolute(_)
+            | IoEvent::ChangeOutputVolume(_, _)
+            | IoEvent::MuteOutput(_, _)
+            | IoEvent::Control(_)
+            | IoEvent::Seek(_, _)
+            | IoEvent::Repeat
+            | IoEvent::Shuffle
+            | IoEvent::ZoneSettingToggle(_)
+            | IoEvent::PauseOnTrackEndReq
+            | IoEvent::SleepTimer(_)
+            | IoEvent::Alarm(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_zone_selection_is_allowed_in_monitor_mode() {
+        let zone = IoEvent::ZoneSelected(EndPoint::Zone("zone".to_owned()));
+        let output = IoEvent::ZoneSelected(EndPoint::Output("output".to_owned()));
+        let preset = IoEvent::ZoneSelected(EndPoint::Preset("preset".to_owned()));
+
+        assert!(!zone.is_control());
+        assert!(output.is_control());
+        assert!(preset.is_control());
+    }
 }
@@ -1,33 +1,51 @@
+use chrono::{DateTime, Local, NaiveTime};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::net::{IpAddr, Ipv4Addr};
-use std::str::FromStr;
-use std::{collections::HashMap, fs, path};
-use std::sync::Arc;
-use tokio::{sync::{mpsc::{Receiver, Sender}, Mutex}, time::{Duration, sleep}, select};
+use std::net::IpAddr;
+use std::{cmp::Reverse, collections::HashMap, fs, path};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::{net::lookup_host, sync::{mpsc::{Receiver, Sender}, Mutex}, time::{Duration, Instant, sleep, sleep_until, timeout_at}, select};
 
 use roon_api::{
     info,
-    browse::{Action, Browse, BrowseOpts, LoadOpts},
+    browse::{self, Action, Browse, BrowseOpts, LoadOpts},
+    Core,
     CoreEvent,
+    image::{Args, Format, Image, Scale, Scaling},
     Info,
     Parsed,
     RoonApi,
     Services,
     Svc,
-    transport::{Control, Output, QueueItem, Repeat, Seek, State, Transport, volume, Zone},
+    transport::{Control, NowPlaying, Output, QueueItem, Repeat, Seek, State, Transport, volume, Zone},
 };
 
-use super::{EndPoint, IoEvent, QueueMode};
+use super::{ConnectionState, EndPoint, IoEvent, QueueAction, QueueMode, ZoneEntry, ZoneSort, ZoneSummary};
 
 const TUI_BROWSE: &str = "tui_browse";
-const QUEUE_ITEM_COUNT: u32 = 100;
+const ART_SIZE: u32 = 240;
+const RETRY_DELAY_MAX: Duration = Duration::from_secs(30);
+// Seconds of queue time remaining at which the queue mode adds to the queue
+const AUTO_QUEUE_WINDOW: i64 = 3;
+const SLEEP_FADE_WINDOW: Duration = Duration::from_secs(30);
+const SLEEP_FADE_STEP: Duration = Duration::from_secs(2);
+// The wall clock is checked regularly, a timer alone misses changes in the local time
+const ALARM_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const ALARM_FORMAT: &str = "%H:%M";
 
 pub struct Options {
     pub config: String,
     pub ip: Option<String>,
-    pub port: String,
+    pub port: u16,
+    pub discovery_interval: Duration,
+    pub core: Option<String>,
+    pub no_art: bool,
+    pub queue_limit: u32,
+    pub sleep_fade: bool,
+    // Set by the app while the zones overview is shown
+    pub overview_open: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -36,21 +54,67 @@ struct Settings {
     profile: Option<String>,
     queue_modes: Option<HashMap<String, QueueMode>>,
     presets: Option<HashMap<String, Vec<(String, Option<f32>)>>>,
+    browse_path: Option<Vec<String>>,
+    bookmarks: Option<HashMap<String, Vec<String>>>,
+    zone_sort: Option<ZoneSort>,
+    zones_used: Option<HashMap<String, u64>>,
+    auto_queue_window: Option<i64>,
+    strict_preset_match: Option<bool>,
+    remaining_time: Option<bool>,
+    split: Option<u16>,
+    // The zone id and the time of day as HH:MM
+    alarm: Option<(String, String)>,
+    selection: Option<Selection>,
+}
+
+// The end point chosen by the user, restored when the zone ids have changed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Selection {
+    // Zone ids change with grouping, a zone is stored by its first output
+    Zone(String),
+    Output(String),
+    Preset(String),
+}
+
+// The output id, the volume before the fade and the minimum volume
+type FadeVolume = (String, f32, f32);
+
+struct SleepTimer {
+    zone_id: String,
+    deadline: Instant,
+    wakeup: Instant,
+    // The start of the fade and the output volumes before it
+    fade: Option<(Instant, Vec<FadeVolume>)>,
 }
 
 struct RoonHandler {
     to_app: Sender<IoEvent>,
     config_path: Arc<String>,
+    core_name: Option<Arc<String>>,
+    queue_limit: u32,
+    sleep_fade: bool,
+    overview_open: Arc<AtomicBool>,
+    queue_item_count: u32,
     settings: Settings,
     browse: Option<Browse>,
     transport: Option<Transport>,
+    image: Option<Image>,
+    image_key: Option<String>,
     zone_map: HashMap<String, Zone>,
     zone_output_ids: Option<Vec<String>>,
     orphaned_output_id: Option<String>,
+    restore_selection: Option<Selection>,
     matched_zones: HashMap<String, String>,
     pause_on_track_end: bool,
+    sleep_timer: Option<SleepTimer>,
+    alarm_at: Option<DateTime<Local>>,
     browse_reached_home: bool,
-    browse_paths: HashMap<String, Vec<&'static str>>,
+    browse_paths: HashMap<String, Vec<String>>,
+    queue_append_pending: HashMap<String, bool>,
+    edit_preset: Option<String>,
+    play_action_levels: Option<u32>,
+    search_pending: bool,
     profiles: Option<Vec<(String, String)>>,
     queue_end: Option<QueueItem>,
     seek_seconds: Option<i32>,
@@ -61,6 +125,14 @@ pub async fn start(options: Options, to_app: Sender<IoEvent>, from_app: Receiver
     let config_path = options.config;
     let ip = options.ip;
     let port = options.port;
+    let retry_delay_min = options.discovery_interval;
+    // A configured interval beyond the maximum disables the backoff
+    let retry_delay_max = retry_delay_min.max(RETRY_DELAY_MAX);
+    let core_name = options.core.map(Arc::new);
+    let no_art = options.no_art;
+    let queue_limit = options.queue_limit;
+    let sleep_fade = options.sleep_fade;
+    let overview_open = options.overview_open;
     let path = path::Path::new(&config_path);
 
     fs::create_dir_all(path.parent().unwrap()).unwrap();
@@ -69,24 +141,46 @@ pub async fn start(options: Options, to_app: Sender<IoEvent>, from_app: Receiver
     let from_app = Arc::new(Mutex::new(from_app));
     let info = info!("com.theappgineer", "Roon TUI");
     let mut roon = RoonApi::new(info);
+    let core_found = Arc::new(AtomicBool::new(false));
+    let reconnect = Arc::new(AtomicBool::new(false));
 
     tokio::spawn(async move {
+        let mut retry_delay = retry_delay_min;
+
         loop {
-            let services = Some(vec![
+            let mut services = vec![
                 Services::Browse(Browse::new()),
                 Services::Transport(Transport::new()),
-            ]);
+            ];
+
+            if !no_art {
+                services.push(Services::Image(Image::new()));
+            }
+
+            let services = Some(services);
             let provided: HashMap<String, Svc> = HashMap::new();
             let config_path_clone = config_path.clone();
             let get_roon_state = move || {
                 RoonApi::load_config(&config_path_clone, "roonstate")
             };
-            let result = match ip.as_deref() {
-                Some(ip) => {
-                    let ip = &IpAddr::V4(Ipv4Addr::from_str(ip).unwrap());
-                    let port = &port;
+            let connection = match ip.as_deref() {
+                Some(ip) => ConnectionState::Connecting(ip.to_owned()),
+                None => ConnectionState::Discovering,
+            };
+
+            if to_app.send(IoEvent::Connection(connection)).await.is_err() {
+                log::info!("App closed, stopping Roon task");
+                break;
+            }
 
-                    roon.ws_connect(Box::new(get_roon_state), provided, services, ip, port).await
+            let result = match ip.as_deref() {
+                Some(host) => {
+                    match resolve_host(host, port).await {
+                        Some(ip) => {
+                            roon.ws_connect(Box::new(get_roon_state), provided, services, &ip, &port.to_string()).await
+                        }
+                        None => None,
+                    }
                 }
                 None => {
                     roon.start_discovery(Box::new(get_roon_state), provided, services).await
@@ -97,15 +191,25 @@ pub async fn start(options: Options, to_app: Sender<IoEvent>, from_app: Receiver
                 let config_path = config_path.clone();
                 let to_app = to_app.clone();
                 let from_app = from_app.clone();
+                let core_name = core_name.clone();
+                let core_found = core_found.clone();
+                let reconnect = reconnect.clone();
+                let overview_open = overview_open.clone();
 
                 handlers.spawn(async move {
-                    let mut roon_handler = RoonHandler::new(to_app, config_path);
+                    let mut roon_handler = RoonHandler::new(to_app, config_path, core_name, queue_limit, sleep_fade, overview_open);
 
                     loop {
                         let mut from_app = from_app.lock().await;
 
                         select! {
                             Some((core_event, msg)) = core_rx.recv() => {
+                                if let CoreEvent::Found(core) = &core_event {
+                                    if roon_handler.is_wanted_core(core) {
+                                        core_found.store(true, Ordering::Relaxed);
+                                    }
+                                }
+
                                 roon_handler.handle_core_event(core_event).await;
 
                                 if let Some((msg, parsed)) = msg {
@@ -113,23 +217,129 @@ pub async fn start(options: Options, to_app: Sender<IoEvent>, from_app: Receiver
                                 }
                             }
                             Some(io_event) = from_app.recv() => {
+                                if let IoEvent::Reconnect = io_event {
+                                    // Ending this task drops the connection tasks
+                                    reconnect.store(true, Ordering::Relaxed);
+                                    break;
+                                }
+
                                 roon_handler.handle_io_event(io_event).await;
                             }
+                            _ = sleep_until(roon_handler.get_sleep_wakeup()), if roon_handler.sleep_timer.is_some() => {
+                                roon_handler.handle_sleep_timer().await;
+                            }
+                            _ = sleep(roon_handler.get_alarm_wait()), if roon_handler.alarm_at.is_some() => {
+                                roon_handler.handle_alarm().await;
+                            }
+                            else => break,
                         };
+
+                        if roon_handler.to_app.is_closed() {
+                            break;
+                        }
                     }
                 });
 
                 handlers.join_next().await;
             }
 
-            sleep(Duration::from_secs(10)).await;
+            if to_app.is_closed() {
+                log::info!("App closed, stopping Roon task");
+                break;
+            }
+
+            // Back off exponentially while no Server is found, a found Server restarts the backoff
+            if core_found.swap(false, Ordering::Relaxed) {
+                retry_delay = retry_delay_min;
+            }
+
+            if reconnect.swap(false, Ordering::Relaxed) {
+                log::info!("Reconnecting to the Roon Server on request");
+                retry_delay = retry_delay_min;
+                continue;
+            }
+
+            log::warn!("Retrying connection to the Roon Server in {} seconds", retry_delay.as_secs());
+            let _ = to_app.send(IoEvent::Connection(ConnectionState::Retrying(retry_delay.as_secs()))).await;
+
+            // A reconnect request cuts the wait short
+            select! {
+                _ = sleep(retry_delay) => retry_delay = (retry_delay * 2).min(retry_delay_max),
+                _ = wait_for_reconnect(&from_app) => retry_delay = retry_delay_min,
+            }
         }
     });
 }
 
+// Waits for a reconnect request while not connected, other requests can't be served then
+async fn wait_for_reconnect(from_app: &Mutex<Receiver<IoEvent>>) {
+    let mut from_app = from_app.lock().await;
+
+    while let Some(io_event) = from_app.recv().await {
+        if let IoEvent::Reconnect = io_event {
+            return;
+        }
+    }
+}
+
+// Accepts a host name as well as an IPv4 or IPv6 address, the first address found is used
+async fn resolve_host(host: &str, port: u16) -> Option<IpAddr> {
+    match lookup_host((host, port)).await {
+        Ok(mut addrs) => {
+            let ip = addrs.next().map(|addr| addr.ip());
+
+            if ip.is_none() {
+                log::error!("No address found for {}", host);
+            }
+
+            ip
+        }
+        Err(err) => {
+            log::error!("Failed to resolve {}: {}", host, err);
+            None
+        }
+    }
+}
+
+// Returns the name and version of the Servers found within the discovery window
+pub async fn discover_cores(config_path: &str, window: Duration) -> Vec<(String, String)> {
+    let info = info!("com.theappgineer", "Roon TUI");
+    let mut roon = RoonApi::new(info);
+    let config_path = config_path.to_owned();
+    let get_roon_state = move || {
+        RoonApi::load_config(&config_path, "roonstate")
+    };
+    let mut cores = Vec::new();
+
+    if let Some((_handlers, mut core_rx)) = roon.start_discovery(Box::new(get_roon_state), HashMap::new(), None).await {
+        let deadline = Instant::now() + window;
+
+        while let Ok(Some((core_event, _))) = timeout_at(deadline, core_rx.recv()).await {
+            if let CoreEvent::Found(core) = core_event {
+                let core = (core.display_name, core.display_version);
+
+                // A Server can be found more than once within the window
+                if !cores.contains(&core) {
+                    cores.push(core);
+                }
+            }
+        }
+    }
+
+    cores
+}
+
 impl RoonHandler {
-    fn new(to_app: Sender<IoEvent>, config_path: Arc<String>) -> Self {
+    fn new(
+        to_app: Sender<IoEvent>,
+        config_path: Arc<String>,
+        core_name: Option<Arc<String>>,
+        queue_limit: u32,
+        sleep_fade: bool,
+        overview_open: Arc<AtomicBool>,
+    ) -> Self {
         let settings: Settings = serde_json::from_value(RoonApi::load_config(&config_path, "settings")).unwrap_or_default();
+        let alarm_at = settings.alarm.as_ref().and_then(|(_, time)| get_next_alarm(time));
         let opts = BrowseOpts {
             multi_session_key: Some(TUI_BROWSE.to_owned()),
             ..Default::default()
@@ -138,16 +348,30 @@ impl RoonHandler {
         Self {
             to_app,
             config_path,
+            core_name,
+            queue_limit,
+            sleep_fade,
+            overview_open,
+            queue_item_count: queue_limit,
             settings,
             browse: None,
             transport: None,
+            image: None,
+            image_key: None,
             zone_map: HashMap::new(),
             zone_output_ids: None,
             orphaned_output_id: None,
+            restore_selection: None,
             matched_zones: HashMap::new(),
             pause_on_track_end: false,
+            sleep_timer: None,
+            alarm_at,
             browse_reached_home: false,
             browse_paths: HashMap::new(),
+            queue_append_pending: HashMap::new(),
+            edit_preset: None,
+            play_action_levels: None,
+            search_pending: false,
             profiles: None,
             queue_end: None,
             seek_seconds: None,
@@ -155,28 +379,62 @@ impl RoonHandler {
         }
     }
 
+    async fn send_to_app(&self, io_event: IoEvent) {
+        if let Err(err) = self.to_app.send(io_event).await {
+            log::warn!("Failed to send to app: {}", err);
+        }
+    }
+
+    // Without a Server name given on the command line any Server will do
+    fn is_wanted_core(&self, core: &Core) -> bool {
+        match self.core_name.as_deref() {
+            Some(name) => name.eq_ignore_ascii_case(&core.display_name),
+            None => true,
+        }
+    }
+
     async fn handle_core_event(&mut self, core_event: CoreEvent) -> Option<()> {
         match core_event {
+            CoreEvent::Found(core) | CoreEvent::Lost(core) if !self.is_wanted_core(&core) => {
+                log::info!("Ignoring Roon Server: {}, version {}", core.display_name, core.display_version);
+            }
             CoreEvent::Found(mut core) => {
                 log::info!("Roon Server found: {}, version {}", core.display_name, core.display_version);
 
                 self.browse = core.get_browse().cloned();
                 self.transport = core.get_transport().cloned();
+                self.image = core.get_image().cloned();
 
                 let browse = self.browse.as_ref()?;
                 let transport = self.transport.as_ref()?;
 
                 self.opts.pop_all = true;
+                self.restore_selection = self.settings.selection.clone();
+
+                if let Some(browse_path) = self.settings.browse_path.as_ref().filter(|path| !path.is_empty()) {
+                    // Return to the last browse location, the steps are taken from the end
+                    self.browse_paths.insert(TUI_BROWSE.to_owned(), browse_path.iter().rev().cloned().collect());
+                }
 
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
                 browse.browse(&self.opts).await;
 
                 transport.subscribe_zones().await;
 
-                self.to_app.send(IoEvent::CoreName(Some(core.display_name))).await.unwrap();
+                self.send_bookmarks().await;
+                self.send_to_app(IoEvent::ZoneSortCurrent(self.settings.zone_sort.to_owned().unwrap_or_default())).await;
+                self.send_to_app(IoEvent::RemainingTime(self.settings.remaining_time.unwrap_or_default())).await;
+
+                if let Some(split) = self.settings.split {
+                    self.send_to_app(IoEvent::Split(split)).await;
+                }
+
+                self.send_to_app(IoEvent::AlarmActive(self.settings.alarm.as_ref().map(|(_, time)| time.to_owned()))).await;
+                self.send_to_app(IoEvent::Connection(ConnectionState::Found(core.display_name))).await;
             }
             CoreEvent::Lost(core) => {
                 log::warn!("Roon Server lost: {}, version {}", core.display_name, core.display_version);
-                self.to_app.send(IoEvent::CoreName(None)).await.unwrap();
+                self.send_to_app(IoEvent::Connection(ConnectionState::Lost(core.display_name))).await;
             }
             _ => ()
         }
@@ -233,15 +491,23 @@ impl RoonHandler {
                     RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
                 }
 
-                let new_zone = match self.settings.zone_id.as_deref() {
+                let mut new_zone = match self.settings.zone_id.as_deref() {
                     Some(zone_id) => !self.zone_map.contains_key(zone_id),
                     None => false,
                 };
 
+                let mut track_changed = false;
+
                 for zone in zones {
+                    track_changed |= self.is_playing_track_changed(&zone);
                     self.zone_map.insert(zone.zone_id.to_owned(), zone);
                 }
 
+                // A track change can come without a seek position of zero, e.g. for streams
+                if track_changed && self.pause_on_track_end {
+                    self.pause_at_track_end().await;
+                }
+
                 if self.zone_output_ids.is_none() {
                     for (_, zone) in &self.zone_map {
                         let mut output_ids = zone.outputs.iter()
@@ -255,16 +521,25 @@ impl RoonHandler {
                         }
                     }
 
+                    if let Some(zone_id) = self.resolve_selection().await {
+                        if self.settings.zone_id.as_ref() != Some(&zone_id) {
+                            self.settings.zone_id = Some(zone_id);
+                            new_zone = true;
+                        }
+                    }
+
                     self.sync_and_save_queue_mode().await;
                     self.send_zone_changed(new_zone).await;
                     self.send_zone_list().await;
+                    self.send_zones_overview().await;
+                    self.send_output_volumes().await;
                 }
             }
             Parsed::ZonesRemoved(zone_ids) => {
                 if let Some(zone_id) = self.settings.zone_id.as_ref() {
                     if zone_ids.contains(zone_id) {
-                        self.to_app.send(IoEvent::ZoneRemoved(zone_id.to_owned())).await.unwrap();
-                        self.to_app.send(IoEvent::ZonePresetMatched(None)).await.unwrap();
+                        self.send_to_app(IoEvent::ZoneRemoved(zone_id.to_owned())).await;
+                        self.send_to_app(IoEvent::ZonePresetMatched(None)).await;
                     }
                 }
 
@@ -283,48 +558,101 @@ impl RoonHandler {
                 } else {
                     self.send_zone_list().await;
                 }
+
+                self.send_zones_overview().await;
             }
             Parsed::ZonesSeek(seeks) => {
                 if let Some(zone_id) = self.settings.zone_id.as_deref() {
                     if let Some(index) = seeks.iter().position(|seek| seek.zone_id == *zone_id) {
                         let seek = seeks[index].to_owned();
 
-                        if let Some(seek_position) = seek.seek_position {
-                            if seek_position == 0 && self.pause_on_track_end {
-                                self.control(zone_id, &Control::Pause).await;
-                                self.pause_on_track_end = false;
-                                self.to_app.send(IoEvent::PauseOnTrackEndActive(self.pause_on_track_end)).await.unwrap();
-                            }
+                        let now_playing = self.zone_map.get(zone_id)
+                            .and_then(|zone| zone.now_playing.as_ref());
+
+                        if self.pause_on_track_end && is_next_track_seek(now_playing, seek.seek_position) {
+                            self.pause_at_track_end().await;
                         }
 
-                        self.to_app.send(IoEvent::ZoneSeek(seek)).await.unwrap();
+                        self.send_to_app(IoEvent::ZoneSeek(seek)).await;
+                    }
+                }
+
+                // Keep the seek positions of the zones overview current
+                for seek in &seeks {
+                    let now_playing = self.zone_map.get_mut(&seek.zone_id)
+                        .and_then(|zone| zone.now_playing.as_mut());
+
+                    if let Some(now_playing) = now_playing {
+                        now_playing.seek_position = seek.seek_position;
                     }
                 }
 
+                self.send_zones_overview().await;
+
+                let auto_queue_window = self.settings.auto_queue_window.unwrap_or(AUTO_QUEUE_WINDOW);
+
                 for seek in seeks {
-                    if seek.queue_time_remaining >= 0 && seek.queue_time_remaining <= 3 {
+                    let in_window = seek.queue_time_remaining >= 0 && seek.queue_time_remaining <= auto_queue_window;
+
+                    // A new track or a grown queue ends the pending append
+                    if !in_window || seek.seek_position == Some(0) {
+                        self.queue_append_pending.remove(&seek.zone_id);
+                    }
+
+                    // Several seek updates land in the window, only the first one appends
+                    if in_window && !self.queue_append_pending.get(&seek.zone_id).copied().unwrap_or_default() {
                         let zone = self.zone_map.get(&seek.zone_id);
 
                         if let Some(browse_path) = self.handle_queue_mode(zone, true).await {
+                            self.queue_append_pending.insert(seek.zone_id.to_owned(), true);
                             self.browse_paths.insert(seek.zone_id, browse_path);
                         }
                     }
                 };
             }
             Parsed::Queue(queue_items) => {
-                self.to_app.send(IoEvent::QueueList(queue_items)).await.unwrap();
+                // A queue filling less than the subscription has nothing beyond the loaded part
+                let complete = queue_items.len() < self.queue_item_count as usize;
+
+                self.send_to_app(IoEvent::QueueList(queue_items, complete)).await;
             },
             Parsed::QueueChanges(queue_changes) => {
-                self.to_app.send(IoEvent::QueueListChanges(queue_changes)).await.unwrap();
+                self.send_to_app(IoEvent::QueueListChanges(queue_changes)).await;
             }
             Parsed::Outputs(outputs) => {
-                let zone_id = self.settings.zone_id.as_deref()?;
-                let zone = self.zone_map.get(zone_id);
-                let grouping = Self::get_grouping(zone, &outputs);
+                let grouping = match self.edit_preset.take() {
+                    Some(preset) => self.get_preset_grouping(&preset, &outputs),
+                    None => {
+                        let zone_id = self.settings.zone_id.as_deref()?;
+                        let zone = self.zone_map.get(zone_id);
 
-                self.to_app.send(IoEvent::ZoneGrouping(grouping)).await.unwrap();
+                        Self::get_grouping(zone, &outputs)
+                    }
+                };
+
+                self.send_output_volumes().await;
+                self.send_to_app(IoEvent::ZoneGrouping(grouping)).await;
             }
             Parsed::BrowseResult(result, multi_session_key) => {
+                if result.action != Action::List && multi_session_key.as_deref() == Some(TUI_BROWSE) {
+                    // No list will be loaded
+                    self.browse_paths.remove(TUI_BROWSE);
+                    self.search_pending = false;
+
+                    if let Some(levels) = self.play_action_levels.take() {
+                        // Return to the list the item was played from
+                        let opts = BrowseOpts {
+                            pop_levels: Some(levels),
+                            multi_session_key: multi_session_key.clone(),
+                            ..Default::default()
+                        };
+
+                        self.browse.as_ref()?.browse(&opts).await;
+                    } else {
+                        self.send_to_app(IoEvent::BrowseLoading(false)).await;
+                    }
+                }
+
                 match result.action {
                     Action::List => {
                         let list = result.list?;
@@ -337,7 +665,9 @@ impl RoonHandler {
                             opts.offset = offset;
                             opts.set_display_offset = offset;
 
-                            self.to_app.send(IoEvent::BrowseTitle(list.title)).await.unwrap();
+                            self.save_browse_path(&list.title, list.level);
+                            self.send_to_app(IoEvent::BrowseBreadcrumb(self.settings.browse_path.clone().unwrap_or_default())).await;
+                            self.send_to_app(IoEvent::BrowseTitle(list.title)).await;
                         } else if list.title == "Albums" || list.title == "Tracks" {
                             let mut rng = rand::thread_rng();
                             let offset = rng.gen_range(0..list.count);
@@ -352,8 +682,8 @@ impl RoonHandler {
                         self.browse.as_ref()?.load(&opts).await;
                     }
                     Action::Message => {
-                        let is_error = result.is_error.unwrap();
-                        let message = result.message.unwrap();
+                        let is_error = result.is_error.unwrap_or_default();
+                        let message = result.message?;
 
                         if is_error && message == "Zone is not configured" {
                             if self.zone_map.is_empty() {
@@ -361,8 +691,10 @@ impl RoonHandler {
                                 self.opts.item_key = None;
                             }
 
-                            self.to_app.send(IoEvent::ZoneSelect).await.unwrap();
+                            self.send_to_app(IoEvent::ZoneSelect).await;
                         }
+
+                        self.send_to_app(IoEvent::BrowseMessage(message, is_error)).await;
                     }
                     _ => (),
                 }
@@ -372,8 +704,21 @@ impl RoonHandler {
 
                 if multi_session_str == TUI_BROWSE {
                     let new_offset = result.offset + result.items.len();
+                    let search_item = self.get_search_item(&result.items);
+                    let replay_item_key = self.get_replay_item_key(&result.items, new_offset >= result.list.count);
+                    let replaying = replay_item_key.is_some();
+
+                    if let Some(item_key) = replay_item_key {
+                        // Continue with the next step instead of loading the remaining items
+                        let opts = BrowseOpts {
+                            item_key,
+                            zone_or_output_id: self.get_browse_zone_id(),
+                            multi_session_key: multi_session_key.clone(),
+                            ..Default::default()
+                        };
 
-                    if new_offset < result.list.count {
+                        self.browse.as_ref()?.browse(&opts).await;
+                    } else if new_offset < result.list.count {
                         // There are more items to load
                         let opts = LoadOpts {
                             offset: new_offset,
@@ -394,7 +739,20 @@ impl RoonHandler {
                     };
 
                     self.browse_reached_home = result.list.level == 0;
-                    self.to_app.send(IoEvent::BrowseList(result.offset, result.items)).await.unwrap();
+                    self.send_to_app(IoEvent::BrowseList(result.offset, result.items)).await;
+
+                    if !replaying && new_offset >= result.list.count {
+                        self.send_to_app(IoEvent::BrowseLoading(false)).await;
+
+                        if self.search_pending {
+                            self.search_pending = false;
+                            self.send_to_app(IoEvent::Message("Search not found")).await;
+                        }
+                    }
+
+                    if let Some(item) = search_item {
+                        self.send_to_app(IoEvent::BrowseSearchPrompt(item)).await;
+                    }
                 } else {
                     let browse_path = self.browse_paths.get_mut(multi_session_str)?;
                     let step = browse_path.pop()?;
@@ -415,6 +773,11 @@ impl RoonHandler {
                         result.items.iter().find_map(|item| if item.title == step {Some(item)} else {None})
                     };
 
+                    if item.is_none() {
+                        // The remaining steps can't be taken anymore
+                        self.browse_paths.remove(multi_session_str);
+                    }
+
                     let opts = BrowseOpts {
                         zone_or_output_id: multi_session_key.clone(),
                         item_key: item?.item_key.clone(),
@@ -425,6 +788,12 @@ impl RoonHandler {
                     self.browse.as_ref()?.browse(&opts).await;
                 }
             }
+            Parsed::Jpeg((image_key, data)) | Parsed::Png((image_key, data)) => {
+                // Skip images of tracks that are no longer playing
+                if self.image_key.as_ref() == Some(&image_key) {
+                    self.send_to_app(IoEvent::ImageData(data)).await;
+                }
+            }
             _ => (),
         }
 
@@ -459,16 +828,9 @@ impl RoonHandler {
 
                 self.opts.item_key = item_key;
 
-                self.opts.zone_or_output_id = if let Some(zone_id) = self.settings.zone_id.as_deref() {
-                    if self.zone_map.contains_key(zone_id) {
-                        self.settings.zone_id.to_owned()
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
+                self.opts.zone_or_output_id = self.get_browse_zone_id();
 
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
                 browse.browse(&self.opts).await;
 
                 self.opts.input = None;
@@ -477,25 +839,118 @@ impl RoonHandler {
                 if !self.browse_reached_home {
                     self.opts.pop_levels = Some(1);
 
+                    self.send_to_app(IoEvent::BrowseLoading(true)).await;
                     browse.browse(&self.opts).await;
                 }
             }
             IoEvent::BrowseRefresh => {
                 self.opts.refresh_list = true;
 
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
                 browse.browse(&self.opts).await;
             }
             IoEvent::BrowseHome => {
                 self.opts.pop_all = true;
 
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
                 browse.browse(&self.opts).await;
             }
             IoEvent::BrowseInput(input) => {
                 self.opts.input = Some(input);
 
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
+                browse.browse(&self.opts).await;
+            }
+            IoEvent::BrowseSearch => {
+                // Search is found within Library, cores that list it on the top level stop there
+                self.browse_paths.insert(TUI_BROWSE.to_owned(), vec!["Library".to_owned()]);
+                self.search_pending = true;
+                self.opts.pop_all = true;
+
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
+                browse.browse(&self.opts).await;
+            }
+            IoEvent::BookmarkAdd(name) => {
+                let browse_path = self.settings.browse_path.clone().unwrap_or_default();
+
+                if self.settings.bookmarks.is_none() {
+                    self.settings.bookmarks = Some(HashMap::new());
+                }
+
+                self.settings.bookmarks.as_mut()?.insert(name, browse_path);
+
+                let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+                RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+
+                self.send_bookmarks().await;
+            }
+            IoEvent::BookmarkSelect(name) => {
+                let browse_path = self.settings.bookmarks.as_ref()?.get(&name)?;
+
+                if !browse_path.is_empty() {
+                    // Replay the bookmarked location from the top level, the steps are taken from the end
+                    self.browse_paths.insert(TUI_BROWSE.to_owned(), browse_path.iter().rev().cloned().collect());
+                }
+
+                self.opts.pop_all = true;
+
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
+                browse.browse(&self.opts).await;
+            }
+            IoEvent::BookmarkDelete(name) => {
+                self.settings.bookmarks.as_mut()?.remove(&name);
+
+                let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+                RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+
+                self.send_bookmarks().await;
+            }
+            IoEvent::BrowsePlayAction(item, queue_action) => {
+                let action = match queue_action {
+                    QueueAction::PlayNow => "Play Now",
+                    QueueAction::AddNext => "Add Next",
+                    QueueAction::Queue => "Queue",
+                };
+                // Lists, like albums, start with an item (e.g. "Play Album") leading to the actions
+                let browse_path = match item.hint {
+                    Some(browse::ItemHint::ActionList) => vec![action.to_owned()],
+                    Some(browse::ItemHint::List) => vec![action.to_owned(), String::new()],
+                    _ => return None,
+                };
+
+                self.play_action_levels = Some(browse_path.len() as u32);
+                self.browse_paths.insert(TUI_BROWSE.to_owned(), browse_path);
+                self.opts.item_key = item.item_key;
+                self.opts.zone_or_output_id = self.get_browse_zone_id();
+
+                self.send_to_app(IoEvent::BrowseLoading(true)).await;
                 browse.browse(&self.opts).await;
             }
             IoEvent::QueueListLast(item) => self.queue_end = item,
+            IoEvent::QueueLoadMore(loaded) => {
+                // Only a completely filled subscription can be followed by more items
+                if loaded >= self.queue_item_count as usize {
+                    self.queue_item_count += self.queue_limit;
+
+                    let transport = self.transport.as_ref()?;
+                    let zone_id = self.settings.zone_id.as_deref()?;
+
+                    transport.unsubscribe_queue().await;
+                    transport.subscribe_queue(zone_id, self.queue_item_count).await;
+                } else {
+                    self.send_to_app(IoEvent::QueueLoading(false)).await;
+                }
+            }
+            IoEvent::QueueResync => {
+                log::warn!("Queue changes out of sync, subscribing again");
+
+                let transport = self.transport.as_ref()?;
+                let zone_id = self.settings.zone_id.as_deref()?;
+
+                transport.unsubscribe_queue().await;
+                transport.subscribe_queue(zone_id, self.queue_item_count).await;
+                self.send_to_app(IoEvent::QueueLoading(true)).await;
+            }
             IoEvent::QueueSelected(queue_item_id) => {
                 let transport = self.transport.as_ref()?;
                 let zone_id = self.settings.zone_id.as_deref()?;
@@ -504,6 +959,7 @@ impl RoonHandler {
             }
             IoEvent::QueueClear => {
                 self.seek_seconds = self.play_queue_end().await;
+                self.send_to_app(IoEvent::Status("Queue cleared".to_owned())).await;
             }
             IoEvent::QueueModeNext => {
                 let queue_mode = self.select_next_queue_mode().await?;
@@ -530,8 +986,13 @@ impl RoonHandler {
 
                 transport.unsubscribe_queue().await;
 
+                // An explicit selection replaces the one still to be restored
+                self.restore_selection = None;
+
                 match end_point {
                     EndPoint::Output(output_id) => {
+                        self.settings.selection = Some(Selection::Output(output_id.to_owned()));
+
                         for (_, zone) in &self.zone_map {
                             let contains_output = zone.outputs.iter()
                                 .any(|output| {
@@ -540,7 +1001,7 @@ impl RoonHandler {
 
                             if contains_output {
                                 self.matched_zones.remove(&zone.zone_id);
-                                self.to_app.send(IoEvent::ZonePresetMatched(None)).await.unwrap();
+                                self.send_to_app(IoEvent::ZonePresetMatched(None)).await;
 
                                 let output_ids = zone.outputs.iter()
                                     .map(|output| {
@@ -555,28 +1016,52 @@ impl RoonHandler {
                         }
                     }
                     EndPoint::Zone(zone_id) => {
-                        transport.subscribe_queue(&zone_id, QUEUE_ITEM_COUNT).await;
+                        self.queue_item_count = self.queue_limit;
+                        transport.subscribe_queue(&zone_id, self.queue_item_count).await;
+                        self.send_to_app(IoEvent::QueueLoading(true)).await;
 
                         if let Some(browse_path) = self.browse_profile().await {
                             self.browse_paths.insert(zone_id.to_owned(), browse_path);
                         }
 
-                        if let Some(zone) = self.zone_map.get(&zone_id) {
+                        if let Some(zone) = self.zone_map.get(&zone_id).cloned() {
                             let matched_preset = self.matched_zones.get(&zone_id).cloned();
 
-                            self.to_app.send(IoEvent::ZonePresetMatched(matched_preset)).await.unwrap();
-                            self.to_app.send(IoEvent::ZoneChanged(zone.to_owned())).await.unwrap();
+                            self.request_image(&zone).await;
+                            self.send_to_app(IoEvent::ZonePresetMatched(matched_preset)).await;
+                            self.send_to_app(IoEvent::ZoneChanged(zone)).await;
+                        }
+
+                        if let Some(output) = self.zone_map.get(&zone_id).and_then(|zone| zone.outputs.first()) {
+                            // Zone ids change with grouping, track the use of its first output instead
+                            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+                            self.settings.zones_used.get_or_insert_with(HashMap::new).insert(output.output_id.to_owned(), now);
+                            self.settings.selection = Some(Selection::Zone(output.output_id.to_owned()));
                         }
 
                         // Store the zone_id in settings before it is used again in sync_and_save_queue_mode
                         self.settings.zone_id = Some(zone_id);
 
                         self.sync_and_save_queue_mode().await;
+
+                        if self.settings.zone_sort == Some(ZoneSort::RecentlyUsed) {
+                            self.send_zone_list().await;
+                        }
                     }
                     EndPoint::Preset(preset) => {
-                        let output_ids = self.settings.presets
-                            .as_ref()?
-                            .get(&preset)?
+                        let preset_output_ids = self.settings.presets.as_ref()?.get(&preset)?;
+
+                        // Grouping with missing outputs would result in a different group
+                        if !self.is_preset_available(preset_output_ids) {
+                            self.send_to_app(IoEvent::Message("Preset has unavailable outputs")).await;
+
+                            return None;
+                        }
+
+                        self.settings.selection = Some(Selection::Preset(preset.to_owned()));
+
+                        let output_ids = preset_output_ids
                             .iter()
                             .map(|(output_id, _)| {
                                 output_id.to_owned()
@@ -587,10 +1072,60 @@ impl RoonHandler {
                     }
                 }
             }
+            IoEvent::ZoneSortNext => {
+                let zone_sort = match self.settings.zone_sort.take().unwrap_or_default() {
+                    ZoneSort::Alphabetical => ZoneSort::RecentlyUsed,
+                    ZoneSort::RecentlyUsed => ZoneSort::PlayingFirst,
+                    ZoneSort::PlayingFirst => ZoneSort::Alphabetical,
+                };
+
+                self.send_to_app(IoEvent::ZoneSortCurrent(zone_sort.to_owned())).await;
+                self.settings.zone_sort = Some(zone_sort);
+
+                let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+                RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+
+                self.send_zone_list().await;
+            }
+            IoEvent::RemainingTimeToggle => {
+                let remaining_time = !self.settings.remaining_time.unwrap_or_default();
+
+                self.send_to_app(IoEvent::RemainingTime(remaining_time)).await;
+                self.settings.remaining_time = Some(remaining_time);
+
+                let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+                RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+            }
+            IoEvent::SplitChanged(split) => {
+                self.settings.split = Some(split);
+
+                let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+                RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+            }
+            IoEvent::ZoneTransfer(zone_id) => {
+                let from_zone_id = self.settings.zone_id.as_deref()?;
+                let from_zone = self.zone_map.get(from_zone_id)?;
+
+                // Only playback can be transferred, and not to the zone itself
+                if from_zone_id != zone_id && from_zone.state != State::Stopped {
+                    self.transport.as_ref()?.transfer_zone(from_zone_id, &zone_id).await;
+                }
+            }
+            IoEvent::ToggleStandby(output_id) => {
+                let output = self.get_output(&output_id)?;
+                let control_key = output.source_controls.as_ref()?
+                    .iter()
+                    .find(|source_control| source_control.supports_standby)?
+                    .control_key.as_str();
+
+                self.transport.as_ref()?.toggle_standby(&output_id, Some(control_key)).await;
+            }
+            IoEvent::ZonesOverviewReq => self.send_zones_overview().await,
             IoEvent::ZoneGroupReq => {
                 self.transport.as_ref()?.get_outputs().await;
             }
             IoEvent::ZoneGrouped(output_ids) => {
+                self.settings.selection = output_ids.first().map(|output_id| Selection::Zone(output_id.to_owned()));
                 self.zone_output_ids = self.update_grouping(output_ids).await;
             }
             IoEvent::ZoneSavePreset(name, mut output_ids) => {
@@ -606,6 +1141,7 @@ impl RoonHandler {
                     self.settings.presets = Some(HashMap::new());
                 }
 
+                self.send_to_app(IoEvent::Status(format!("Preset {} saved", name))).await;
                 self.settings.presets.as_mut()?.insert(name, preset);
 
                 let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
@@ -621,10 +1157,14 @@ impl RoonHandler {
 
                 self.send_zone_list().await;
             }
+            IoEvent::ZoneEditPreset(preset) => {
+                self.edit_preset = Some(preset);
+                self.transport.as_ref()?.get_outputs().await;
+            }
             IoEvent::ZoneMatchPreset(mut output_ids) => {
                 let preset = self.match_preset(&mut output_ids);
 
-                self.to_app.send(IoEvent::ZonePresetMatched(preset)).await.unwrap();
+                self.send_to_app(IoEvent::ZonePresetMatched(preset)).await;
             }
             IoEvent::Mute(how) => {
                 self.mute(&how).await;
@@ -632,6 +1172,17 @@ impl RoonHandler {
             IoEvent::ChangeVolume(steps) => {
                 self.change_volume(steps).await;
             }
+            IoEvent::SetVolumeAbsolute(value) => {
+                self.set_volume_absolute(value).await;
+            }
+            IoEvent::ChangeOutputVolume(output_id, steps) => {
+                let output = self.get_output(&output_id)?;
+
+                self.change_output_volume(output, steps).await;
+            }
+            IoEvent::MuteOutput(output_id, how) => {
+                self.transport.as_ref()?.mute(&output_id, &how).await;
+            }
             IoEvent::Control(how) => {
                 let zone_id = self.settings.zone_id.as_deref()?;
                 let zone_option = self.zone_map.get(zone_id);
@@ -648,15 +1199,29 @@ impl RoonHandler {
                     }
                 }
             }
+            IoEvent::Seek(how, seconds) => {
+                let zone_id = self.settings.zone_id.as_deref()?;
+
+                self.transport.as_ref()?.seek(zone_id, &how, seconds).await;
+            }
             IoEvent::Repeat => {
                 self.toggle_repeat().await;
             }
             IoEvent::Shuffle => {
                 self.toggle_shuffle().await;
             }
+            IoEvent::ZoneSettingToggle(name) => {
+                self.toggle_zone_setting(&name).await;
+            }
             IoEvent::PauseOnTrackEndReq => {
                 self.pause_on_track_end = self.handle_pause_on_track_end_req().unwrap_or_default();
-                self.to_app.send(IoEvent::PauseOnTrackEndActive(self.pause_on_track_end)).await.unwrap();
+                self.send_to_app(IoEvent::PauseOnTrackEndActive(self.pause_on_track_end)).await;
+            }
+            IoEvent::SleepTimer(minutes) => {
+                self.set_sleep_timer(minutes).await;
+            }
+            IoEvent::Alarm(time) => {
+                self.set_alarm(time).await;
             }
             _ => (),
         }
@@ -685,83 +1250,475 @@ impl RoonHandler {
         Some(grouping)
     }
 
-    fn match_preset(&self, output_ids: &mut Vec<String>) -> Option<String> {
-        output_ids[1..].sort();
-
-        self.settings.presets.as_ref()?.iter()
-            .find_map(|(preset, preset_output_ids)| {
-                if output_ids.len() == preset_output_ids.len() {
-                    let matches = preset_output_ids.iter()
-                        .filter(|(preset_output_id, _)| {
-                            output_ids.contains(&preset_output_id)
-                        })
-                        .count() == preset_output_ids.len();
-
-                    if matches {
-                        Some(preset.to_owned())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-    }
+    // Lists the available outputs of the preset as included, followed by the outputs its lead can group with
+    fn get_preset_grouping(&self, preset: &str, outputs: &[Output]) -> Option<Vec<(String, String, bool)>> {
+        let preset_output_ids = self.settings.presets.as_ref()?.get(preset)?;
+        let preset_outputs = preset_output_ids.iter()
+            .filter_map(|(output_id, _)| outputs.iter().find(|output| output.output_id == *output_id))
+            .collect::<Vec<_>>();
+        let can_group_with_output_ids = &preset_outputs.first()?.can_group_with_output_ids;
+        let mut grouping = preset_outputs.iter()
+            .map(|output| (output.output_id.to_owned(), output.display_name.to_owned(), true))
+            .collect::<Vec<_>>();
 
-    fn handle_pause_on_track_end_req(&self) -> Option<bool> {
-        let zone_id = self.settings.zone_id.as_deref()?;
-        let zone = self.zone_map.get(zone_id)?;
-        let now_playing_length = zone.now_playing.as_ref()?.length?;
+        for output in outputs {
+            let is_not_in = !grouping.iter().any(|(output_id, _, _)| *output_id == output.output_id);
+
+            if is_not_in && can_group_with_output_ids.contains(&output.output_id) {
+                grouping.push((output.output_id.to_owned(), output.display_name.to_owned(), false));
+            }
+        }
 
-        Some(zone.state == State::Playing && now_playing_length > 0)
+        Some(grouping)
     }
 
-    fn get_profile_name(&self, item_key: Option<&str>) -> Option<String> {
-        let profiles = self.profiles.as_ref()?;
+    // Returns the zone of the restored selection, a preset is grouped again when needed
+    async fn resolve_selection(&mut self) -> Option<String> {
+        let zone_id = match self.restore_selection.as_ref()? {
+            Selection::Zone(output_id) => self.get_zone_id_by_output(output_id, false),
+            Selection::Output(output_id) => self.get_zone_id_by_output(output_id, true),
+            Selection::Preset(preset) => {
+                let zone_id = self.matched_zones.iter()
+                    .find_map(|(zone_id, matched)| if matched == preset {Some(zone_id.to_owned())} else {None});
+
+                if zone_id.is_none() {
+                    let preset_output_ids = match self.settings.presets.as_ref().and_then(|presets| presets.get(preset)) {
+                        Some(preset_output_ids) => preset_output_ids,
+                        None => {
+                            // The preset was deleted
+                            self.restore_selection = None;
+
+                            return None;
+                        }
+                    };
 
-        profiles.iter().find_map(|(key, title)| {
-            if key == item_key? {
-                Some(title.to_owned())
-            } else {
-                None
-            }
-        })
-    }
+                    if !self.is_preset_available(preset_output_ids) {
+                        // Keep waiting for the outputs to come online
+                        log::debug!("Preset {} has unavailable outputs", preset);
 
-    async fn browse_profile(&self) -> Option<Vec<&'static str>> {
-        let zone_id = self.settings.zone_id.as_deref()?;
-        let opts = BrowseOpts {
-            multi_session_key: Some(zone_id.to_owned()),
-            ..Default::default()
+                        return None;
+                    }
+
+                    let output_ids = preset_output_ids.iter()
+                        .map(|(output_id, _)| output_id.to_owned())
+                        .collect();
+
+                    self.restore_selection = None;
+                    self.zone_output_ids = self.update_grouping(output_ids).await;
+
+                    return None;
+                }
+
+                zone_id
+            }
         };
 
-        self.browse.as_ref()?.browse(&opts).await;
+        // Wait for the output to come online otherwise
+        if zone_id.is_some() {
+            self.restore_selection = None;
+        }
 
-        Some(vec!["", "Profile", "Settings"])
+        zone_id
     }
 
-    async fn send_zone_list(&self) {
-        let name_sort = |a: &(EndPoint, String), b: &(EndPoint, String)| a.1.cmp(&b.1);
-        let mut zones = self.zone_map
-            .iter()
-            .map(|(zone_id, zone)| {
-                let display_name = match self.matched_zones.get(zone_id) {
-                    Some(preset) => preset.as_str(),
-                    None => zone.display_name.as_str(),
-                };
+    // Prefers the zone led by the output, or the zone of the output alone, over a group containing it
+    fn get_zone_id_by_output(&self, output_id: &str, alone: bool) -> Option<String> {
+        let contains_output = |zone: &&Zone| zone.outputs.iter().any(|output| output.output_id == output_id);
+        let zone = self.zone_map.values()
+            .find(|zone| {
+                let leads = zone.outputs.first().map(|output| output.output_id == output_id).unwrap_or_default();
 
-                (EndPoint::Zone(zone_id.to_owned()), display_name.to_owned())
+                leads && (!alone || zone.outputs.len() == 1)
             })
-            .collect::<Vec<_>>();
+            .or_else(|| self.zone_map.values().find(contains_output))?;
+
+        Some(zone.zone_id.to_owned())
+    }
+
+    fn is_preset_available(&self, preset_output_ids: &[(String, Option<f32>)]) -> bool {
+        preset_output_ids.iter().all(|(output_id, _)| {
+            self.zone_map.values()
+                .any(|zone| zone.outputs.iter().any(|output| output.output_id == *output_id))
+        })
+    }
+
+    fn match_preset(&self, output_ids: &mut Vec<String>) -> Option<String> {
+        let strict = self.settings.strict_preset_match.unwrap_or_default();
+
+        get_matching_preset(self.settings.presets.as_ref()?, output_ids, strict)
+    }
+
+    // The now playing info doesn't carry a queue_item_id, compare the track info instead
+    fn is_playing_track_changed(&self, zone: &Zone) -> bool {
+        if self.settings.zone_id.as_deref() != Some(zone.zone_id.as_str()) {
+            return false;
+        }
+
+        match self.zone_map.get(&zone.zone_id) {
+            Some(previous) => !is_same_track(previous.now_playing.as_ref(), zone.now_playing.as_ref()),
+            None => false,
+        }
+    }
+
+    async fn pause_at_track_end(&mut self) -> Option<()> {
+        let zone_id = self.settings.zone_id.as_deref()?;
+
+        self.control(zone_id, &Control::Pause).await;
+        self.pause_on_track_end = false;
+        self.send_to_app(IoEvent::PauseOnTrackEndActive(self.pause_on_track_end)).await;
+
+        Some(())
+    }
+
+    async fn set_sleep_timer(&mut self, minutes: u32) -> Option<()> {
+        // A running fade is undone before the timer is replaced
+        if let Some(sleep_timer) = self.sleep_timer.take() {
+            self.restore_fade_volumes(sleep_timer).await;
+        }
+
+        let zone_id = self.settings.zone_id.as_deref()?;
+        let zone = self.zone_map.get(zone_id)?;
+
+        self.sleep_timer = if minutes > 0 && zone.state == State::Playing {
+            let deadline = Instant::now() + Duration::from_secs(minutes as u64 * 60);
+            let wakeup = if self.sleep_fade {
+                deadline.checked_sub(SLEEP_FADE_WINDOW).unwrap_or(deadline).max(Instant::now())
+            } else {
+                deadline
+            };
+
+            Some(SleepTimer {
+                zone_id: zone_id.to_owned(),
+                deadline,
+                wakeup,
+                fade: None,
+            })
+        } else {
+            None
+        };
+
+        if minutes > 0 && self.sleep_timer.is_none() {
+            self.send_to_app(IoEvent::Message("Nothing playing to set the sleep timer for")).await;
+        }
+
+        let deadline = self.sleep_timer.as_ref().map(|sleep_timer| sleep_timer.deadline.into_std());
+
+        self.send_to_app(IoEvent::SleepTimerActive(deadline)).await;
+
+        Some(())
+    }
+
+    // Only awaited while a sleep timer is set
+    fn get_sleep_wakeup(&self) -> Instant {
+        match self.sleep_timer.as_ref() {
+            Some(sleep_timer) => sleep_timer.wakeup,
+            None => Instant::now(),
+        }
+    }
+
+    async fn handle_sleep_timer(&mut self) -> Option<()> {
+        let mut sleep_timer = self.sleep_timer.take()?;
+        let now = Instant::now();
+
+        if now >= sleep_timer.deadline {
+            log::info!("Sleep timer ended, pausing zone {}", sleep_timer.zone_id);
+            self.control(&sleep_timer.zone_id, &Control::Pause).await;
+            self.restore_fade_volumes(sleep_timer).await;
+            self.send_to_app(IoEvent::SleepTimerActive(None)).await;
+
+            return Some(());
+        }
+
+        if sleep_timer.fade.is_none() {
+            sleep_timer.fade = Some((now, self.get_fade_volumes(&sleep_timer.zone_id)));
+        }
+
+        if let Some((fade_start, volumes)) = sleep_timer.fade.as_ref() {
+            let fade_len = sleep_timer.deadline.duration_since(*fade_start).as_secs_f32();
+            let progress = now.duration_since(*fade_start).as_secs_f32() / fade_len.max(1.0);
+
+            for (output_id, value, min) in volumes {
+                let faded = value - (value - min) * progress.min(1.0);
+
+                self.transport.as_ref()?.change_volume(
+                    output_id,
+                    &volume::ChangeMode::Absolute, faded.round() as i32
+                ).await;
+            }
+        }
+
+        sleep_timer.wakeup = (now + SLEEP_FADE_STEP).min(sleep_timer.deadline);
+        self.sleep_timer = Some(sleep_timer);
+
+        Some(())
+    }
+
+    // Outputs without a volume range, like incremental ones, can't be faded and are only paused
+    fn get_fade_volumes(&self, zone_id: &str) -> Vec<FadeVolume> {
+        let outputs = self.zone_map.get(zone_id)
+            .map(|zone| zone.outputs.as_slice())
+            .unwrap_or_default();
+
+        outputs.iter()
+            .filter_map(|output| {
+                let volume = output.volume.as_ref()?;
+
+                match volume.scale {
+                    volume::Scale::Decibel | volume::Scale::Number => {
+                        Some((output.output_id.to_owned(), volume.value?, volume.min?))
+                    }
+                    volume::Scale::Incremental => None,
+                }
+            })
+            .collect()
+    }
+
+    async fn restore_fade_volumes(&self, sleep_timer: SleepTimer) -> Option<()> {
+        let (_, volumes) = sleep_timer.fade?;
+
+        for (output_id, value, _) in volumes {
+            self.transport.as_ref()?.change_volume(
+                &output_id,
+                &volume::ChangeMode::Absolute, value.round() as i32
+            ).await;
+        }
+
+        Some(())
+    }
+
+    async fn set_alarm(&mut self, time: Option<String>) -> Option<()> {
+        self.settings.alarm = match time {
+            Some(time) => Some((self.settings.zone_id.to_owned()?, time)),
+            None => None,
+        };
+        self.alarm_at = self.settings.alarm.as_ref().and_then(|(_, time)| get_next_alarm(time));
+
+        let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+        RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+
+        self.send_to_app(IoEvent::AlarmActive(self.settings.alarm.as_ref().map(|(_, time)| time.to_owned()))).await;
+
+        Some(())
+    }
+
+    // Only awaited while an alarm is set
+    fn get_alarm_wait(&self) -> Duration {
+        match self.alarm_at {
+            Some(alarm_at) => (alarm_at - Local::now()).to_std().unwrap_or_default().min(ALARM_CHECK_INTERVAL),
+            None => ALARM_CHECK_INTERVAL,
+        }
+    }
+
+    async fn handle_alarm(&mut self) -> Option<()> {
+        if Local::now() < self.alarm_at? {
+            return Some(());
+        }
+
+        self.alarm_at = None;
+
+        let (zone_id, time) = self.settings.alarm.take()?;
+        let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+        RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+
+        self.send_to_app(IoEvent::AlarmActive(None)).await;
+
+        // Resume what is in the queue, an empty queue is filled using the queue mode
+        match self.zone_map.get(&zone_id) {
+            Some(zone) if zone.now_playing.is_some() => {
+                log::info!("Alarm of {}, starting playback in zone {}", time, zone.display_name);
+                self.control(&zone_id, &Control::Play).await;
+            }
+            Some(zone) => {
+                log::info!("Alarm of {}, starting the queue mode in zone {}", time, zone.display_name);
+
+                match self.handle_queue_mode(Some(zone), true).await {
+                    Some(browse_path) => {
+                        self.browse_paths.insert(zone_id, browse_path);
+                    }
+                    None => log::warn!("Alarm of {}, nothing to play in zone {}", time, zone_id),
+                }
+            }
+            None => log::warn!("Alarm of {}, zone {} not available, skipping", time, zone_id),
+        }
+
+        Some(())
+    }
+
+    // A track of unknown length can still be paused at the next track boundary
+    fn handle_pause_on_track_end_req(&self) -> Option<bool> {
+        let zone_id = self.settings.zone_id.as_deref()?;
+        let zone = self.zone_map.get(zone_id)?;
+
+        zone.now_playing.as_ref()?;
+
+        Some(zone.state == State::Playing)
+    }
+
+    fn get_profile_name(&self, item_key: Option<&str>) -> Option<String> {
+        let profiles = self.profiles.as_ref()?;
+
+        profiles.iter().find_map(|(key, title)| {
+            if key == item_key? {
+                Some(title.to_owned())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn get_browse_zone_id(&self) -> Option<String> {
+        let zone_id = self.settings.zone_id.as_deref()?;
+
+        if self.zone_map.contains_key(zone_id) {
+            Some(zone_id.to_owned())
+        } else {
+            None
+        }
+    }
+
+    // Returns the item that takes the search input, ending the replay toward it
+    fn get_search_item(&mut self, items: &[browse::Item]) -> Option<browse::Item> {
+        if !self.search_pending {
+            return None;
+        }
+
+        let item = items.iter().find(|item| item.input_prompt.is_some())?;
+
+        self.search_pending = false;
+        self.browse_paths.remove(TUI_BROWSE);
+
+        Some(item.clone())
+    }
+
+    // Returns the item to select when replaying the last browse location
+    fn get_replay_item_key(&mut self, items: &[browse::Item], last_chunk: bool) -> Option<Option<String>> {
+        let browse_path = self.browse_paths.get_mut(TUI_BROWSE)?;
+        let step = browse_path.last()?;
+        let item = if step.is_empty() {
+            items.first()
+        } else {
+            items.iter().find(|item| item.title == *step)
+        };
+        let item_key = match item {
+            Some(item) => {
+                browse_path.pop();
+                Some(item.item_key.clone())
+            }
+            None if last_chunk => {
+                // The location no longer exists, stay where the replay got to
+                browse_path.clear();
+                self.play_action_levels = None;
+                None
+            }
+            None => None,
+        };
+
+        if browse_path.is_empty() {
+            self.browse_paths.remove(TUI_BROWSE);
+        }
+
+        item_key
+    }
+
+    fn save_browse_path(&mut self, title: &str, level: u32) {
+        let mut browse_path = self.settings.browse_path.clone().unwrap_or_default();
+
+        // The list title is the title of the item selected on the level above
+        browse_path.truncate((level as usize).saturating_sub(1));
+
+        if level > 0 {
+            browse_path.push(title.to_owned());
+        }
+
+        if self.settings.browse_path.as_ref() != Some(&browse_path) {
+            self.settings.browse_path = Some(browse_path);
+
+            let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
+            RoonApi::save_config(&self.config_path, "settings", settings).unwrap();
+        }
+    }
+
+    async fn browse_profile(&self) -> Option<Vec<String>> {
+        let zone_id = self.settings.zone_id.as_deref()?;
+        let opts = BrowseOpts {
+            multi_session_key: Some(zone_id.to_owned()),
+            ..Default::default()
+        };
+
+        self.browse.as_ref()?.browse(&opts).await;
+
+        Some(["", "Profile", "Settings"].map(String::from).to_vec())
+    }
+
+    async fn send_bookmarks(&self) {
+        let mut bookmarks = self.settings.bookmarks.as_ref()
+            .map(|bookmarks| bookmarks.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        bookmarks.sort();
+
+        self.send_to_app(IoEvent::Bookmarks(bookmarks)).await;
+    }
+
+    fn get_zone(&self, end_point: &EndPoint) -> Option<&Zone> {
+        match end_point {
+            EndPoint::Zone(zone_id) => self.zone_map.get(zone_id),
+            _ => None,
+        }
+    }
+
+    async fn send_zone_list(&self) {
+        let name_sort = |a: &ZoneEntry, b: &ZoneEntry| a.1.cmp(&b.1);
+        let mut zones = self.zone_map
+            .iter()
+            .map(|(zone_id, zone)| {
+                let display_name = match self.matched_zones.get(zone_id) {
+                    Some(preset) => preset.as_str(),
+                    None => zone.display_name.as_str(),
+                };
+
+                // Grouped zones carry the names of their outputs
+                let outputs = if zone.outputs.len() > 1 {
+                    zone.outputs.iter().map(|output| output.display_name.to_owned()).collect()
+                } else {
+                    Vec::new()
+                };
+                let standby = match zone.outputs.as_slice() {
+                    [output] => get_standby(output),
+                    _ => None,
+                };
+
+                (EndPoint::Zone(zone_id.to_owned()), display_name.to_owned(), Some(zone.state.to_owned()), outputs, standby)
+            })
+            .collect::<Vec<_>>();
 
         zones.sort_by(name_sort);
 
+        // Stable sorts keep the alphabetical order within equal keys
+        match self.settings.zone_sort.as_ref() {
+            Some(ZoneSort::RecentlyUsed) => {
+                zones.sort_by_key(|(end_point, _, _, _, _)| {
+                    let last_used = self.get_zone(end_point)
+                        .and_then(|zone| zone.outputs.first())
+                        .and_then(|output| self.settings.zones_used.as_ref()?.get(&output.output_id));
+
+                    Reverse(last_used.cloned())
+                });
+            }
+            Some(ZoneSort::PlayingFirst) => {
+                zones.sort_by_key(|(_, _, state, _, _)| *state != Some(State::Playing));
+            }
+            _ => (),
+        }
+
         let mut outputs = Vec::new();
 
         for (_, zone) in &self.zone_map {
             if zone.outputs.len() > 1 {
                 let new = zone.outputs.iter().map(|output| {
-                    (EndPoint::Output(output.output_id.to_owned()), output.display_name.to_owned())
+                    let standby = get_standby(output);
+
+                    (EndPoint::Output(output.output_id.to_owned()), output.display_name.to_owned(), None, Vec::new(), standby)
                 }).collect();
 
                 outputs = [outputs, new].concat();
@@ -773,7 +1730,7 @@ impl RoonHandler {
 
         if let Some(presets) = self.settings.presets.as_ref() {
             let mut presets = presets.iter()
-                .filter_map(|(preset, _)| {
+                .filter_map(|(preset, preset_output_ids)| {
                     let matched = self.matched_zones.iter()
                         .find(|(_, matched_preset)| {
                             *matched_preset == preset
@@ -782,7 +1739,13 @@ impl RoonHandler {
                     if matched.is_some() {
                         None
                     } else {
-                        Some((EndPoint::Preset(preset.to_owned()), preset.to_owned()))
+                        let name = if self.is_preset_available(preset_output_ids) {
+                            preset.to_owned()
+                        } else {
+                            format!("{} (unavailable)", preset)
+                        };
+
+                        Some((EndPoint::Preset(preset.to_owned()), name, None, Vec::new(), None))
                     }
                 })
                 .collect::<Vec<_>>();
@@ -791,7 +1754,54 @@ impl RoonHandler {
             zones = [zones, presets].concat();
         }
 
-        self.to_app.send(IoEvent::Zones(zones)).await.unwrap();
+        self.send_to_app(IoEvent::Zones(zones)).await;
+    }
+
+    async fn send_zones_overview(&self) {
+        // Seek updates come in every second, only build the overview when it is shown
+        if !self.overview_open.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut summaries = self.zone_map
+            .iter()
+            .filter_map(|(zone_id, zone)| {
+                let now_playing = zone.now_playing.as_ref()?;
+                let display_name = match self.matched_zones.get(zone_id) {
+                    Some(preset) => preset.as_str(),
+                    None => zone.display_name.as_str(),
+                };
+
+                Some(ZoneSummary {
+                    zone_id: zone_id.to_owned(),
+                    display_name: display_name.to_owned(),
+                    state: zone.state.to_owned(),
+                    title: now_playing.three_line.line1.to_owned(),
+                    seek_position: now_playing.seek_position,
+                    length: now_playing.length,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Playing zones first, alphabetical within
+        summaries.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        summaries.sort_by_key(|summary| summary.state != State::Playing);
+
+        self.send_to_app(IoEvent::ZonesOverview(summaries)).await;
+    }
+
+    async fn request_image(&mut self, zone: &Zone) -> Option<()> {
+        let image_key = zone.now_playing.as_ref()?.image_key.to_owned();
+
+        if image_key != self.image_key {
+            self.image_key = image_key;
+
+            let args = Args::new(Some(Scale::new(Scaling::Fit, ART_SIZE, ART_SIZE)), Some(Format::Jpeg));
+
+            self.image.as_ref()?.get_image(self.image_key.as_deref()?, args).await;
+        }
+
+        Some(())
     }
 
     async fn send_zone_changed(&mut self, new_zone: bool) -> Option<()> {
@@ -799,8 +1809,10 @@ impl RoonHandler {
         let zone = self.zone_map.get(zone_id).cloned()?;
 
         if new_zone {
+            self.queue_item_count = self.queue_limit;
             self.transport.as_ref()?
-                .subscribe_queue(&zone_id, QUEUE_ITEM_COUNT).await;
+                .subscribe_queue(&zone_id, self.queue_item_count).await;
+            self.send_to_app(IoEvent::QueueLoading(true)).await;
 
             if let Some(browse_path) = self.browse_profile().await {
                 self.browse_paths.insert(zone_id.to_owned(), browse_path);
@@ -810,10 +1822,26 @@ impl RoonHandler {
             self.transport.as_ref()?.get_zones().await;
         }
 
+        // A stopped or paused zone, or another zone being selected, ends the sleep timer
+        let sleep_timer_ended = match self.sleep_timer.as_ref() {
+            Some(sleep_timer) => {
+                sleep_timer.zone_id != zone_id || matches!(zone.state, State::Paused | State::Stopped)
+            }
+            None => false,
+        };
+
+        if sleep_timer_ended {
+            if let Some(sleep_timer) = self.sleep_timer.take() {
+                self.restore_fade_volumes(sleep_timer).await;
+            }
+
+            self.send_to_app(IoEvent::SleepTimerActive(None)).await;
+        }
+
         if zone.state != State::Playing {
             if self.pause_on_track_end {
                 self.pause_on_track_end = false;
-                self.to_app.send(IoEvent::PauseOnTrackEndActive(self.pause_on_track_end)).await.unwrap();
+                self.send_to_app(IoEvent::PauseOnTrackEndActive(self.pause_on_track_end)).await;
             }
         } else {
             let seek_seconds = self.seek_seconds.take();
@@ -822,8 +1850,9 @@ impl RoonHandler {
 
         let matched_preset = self.matched_zones.get(zone_id).cloned();
 
-        self.to_app.send(IoEvent::ZonePresetMatched(matched_preset)).await.unwrap();
-        self.to_app.send(IoEvent::ZoneChanged(zone)).await.unwrap();
+        self.request_image(&zone).await;
+        self.send_to_app(IoEvent::ZonePresetMatched(matched_preset)).await;
+        self.send_to_app(IoEvent::ZoneChanged(zone)).await;
 
         Some(())
     }
@@ -855,7 +1884,7 @@ impl RoonHandler {
             QueueMode::default()
         };
 
-        self.to_app.send(IoEvent::QueueModeCurrent(queue_mode.to_owned())).await.unwrap();
+        self.send_to_app(IoEvent::QueueModeCurrent(queue_mode.to_owned())).await;
         self.settings.queue_modes.as_mut()?.insert(output_id.to_owned(), queue_mode);
 
         let settings = self.settings.serialize(serde_json::value::Serializer).unwrap();
@@ -864,7 +1893,7 @@ impl RoonHandler {
         Some(())
     }
 
-    async fn select_next_queue_mode<'a>(&'a mut self) -> Option<&'a QueueMode> {
+    async fn select_next_queue_mode(&mut self) -> Option<QueueMode> {
         let zone_id = self.settings.zone_id.as_deref()?;
         let output_id = self.zone_map.get(zone_id)?.outputs.get(0)?.output_id.as_str();
 
@@ -901,9 +1930,9 @@ impl RoonHandler {
             };
 
             queue_mode
-        };
+        }.to_owned();
 
-        self.to_app.send(IoEvent::QueueModeCurrent(queue_mode.to_owned())).await.unwrap();
+        self.send_to_app(IoEvent::QueueModeCurrent(queue_mode.to_owned())).await;
 
         Some(queue_mode)
     }
@@ -912,7 +1941,7 @@ impl RoonHandler {
         &self,
         zone: Option<&Zone>,
         play: bool,
-    ) -> Option<Vec<&'static str>> {
+    ) -> Option<Vec<String>> {
         let zone = zone?;
         let zone_id = zone.zone_id.as_str();
         let output_id = zone.outputs.get(0)?.output_id.as_str();
@@ -936,7 +1965,7 @@ impl RoonHandler {
 
                 self.browse.as_ref()?.browse(&opts).await;
 
-                Some(vec![play_action, "Play Album", "", "Albums", "Library"])
+                Some([play_action, "Play Album", "", "Albums", "Library"].map(String::from).to_vec())
             }
             QueueMode::RandomTrack => {
                 let opts = BrowseOpts {
@@ -947,7 +1976,7 @@ impl RoonHandler {
 
                 self.browse.as_ref()?.browse(&opts).await;
 
-                Some(vec![play_action, "", "Tracks", "Library"])
+                Some([play_action, "", "Tracks", "Library"].map(String::from).to_vec())
             }
             _ => None,
         }
@@ -971,10 +2000,65 @@ impl RoonHandler {
         let mut req_ids = Vec::new();
 
         for output in &zone.outputs {
-            req_ids.push(self.transport.as_ref()?.change_volume(
-                &output.output_id,
-                &volume::ChangeMode::RelativeStep, steps
-            ).await?);
+            req_ids.push(self.change_output_volume(output, steps).await?);
+        }
+
+        Some(req_ids)
+    }
+
+    async fn change_output_volume(&self, output: &Output, steps: i32) -> Option<usize> {
+        // Incremental outputs only support single steps up or down
+        let steps = match output.volume.as_ref().map(|volume| &volume.scale) {
+            Some(volume::Scale::Incremental) => steps.signum(),
+            _ => steps,
+        };
+
+        self.transport.as_ref()?.change_volume(
+            &output.output_id,
+            &volume::ChangeMode::RelativeStep, steps
+        ).await
+    }
+
+    fn get_output(&self, output_id: &str) -> Option<&Output> {
+        self.zone_map.values()
+            .flat_map(|zone| zone.outputs.iter())
+            .find(|output| output.output_id == output_id)
+    }
+
+    async fn send_output_volumes(&self) {
+        let output_volumes = self.zone_map.values()
+            .flat_map(|zone| zone.outputs.iter())
+            .filter_map(|output| Some((output.output_id.to_owned(), output.volume.to_owned()?)))
+            .collect();
+
+        self.send_to_app(IoEvent::OutputVolumes(output_volumes)).await;
+    }
+
+    async fn set_volume_absolute(&self, value: f32) -> Option<Vec<usize>> {
+        let zone_id = self.settings.zone_id.as_deref()?;
+        let zone = self.zone_map.get(zone_id)?;
+        let mut req_ids = Vec::new();
+
+        for output in &zone.outputs {
+            let volume = match output.volume.as_ref() {
+                Some(volume) => volume,
+                None => continue,
+            };
+
+            match volume.scale {
+                volume::Scale::Decibel | volume::Scale::Number => {
+                    // Keep grouped outputs with different ranges within their own limits
+                    let min = volume.min.unwrap_or(value);
+                    let max = volume.max.unwrap_or(value);
+                    let value = value.clamp(min.min(max), max.max(min)).round() as i32;
+
+                    req_ids.push(self.transport.as_ref()?.change_volume(
+                        &output.output_id,
+                        &volume::ChangeMode::Absolute, value
+                    ).await?);
+                }
+                volume::Scale::Incremental => (),
+            }
         }
 
         Some(req_ids)
@@ -1041,17 +2125,29 @@ impl RoonHandler {
         self.transport.as_ref()?.change_settings(zone_id, settings).await
     }
 
+    async fn toggle_zone_setting(&self, name: &str) -> Option<usize> {
+        let zone_id = self.settings.zone_id.as_deref()?;
+        let mut settings = serde_json::to_value(&self.zone_map.get(zone_id)?.settings).ok()?;
+        let setting = settings.get_mut(name)?;
+
+        // Only boolean settings can be toggled
+        *setting = Value::Bool(!setting.as_bool()?);
+        self.transport.as_ref()?.change_settings(zone_id, serde_json::from_value(settings).ok()?).await
+    }
+
     async fn update_grouping(&mut self, mut new_ids: Vec<String>) -> Option<Vec<String>> {
         let output_ids = new_ids.iter()
             .map(|output_id| output_id.as_str())
             .collect::<Vec<_>>();
+        // Without strict matching a group with a different lead output is kept as is
+        let strict = self.settings.strict_preset_match.unwrap_or_default();
 
         for (_, zone) in &self.zone_map {
             let current_ids = zone.outputs.iter()
                 .map(|output| output.output_id.as_str())
                 .collect::<Vec<_>>();
             let matches_all = output_ids.len() == current_ids.len()
-                && output_ids.get(0) == current_ids.get(0)
+                && (!strict || output_ids.get(0) == current_ids.get(0))
                 && output_ids.iter()
                     .all(|output_id| current_ids.contains(output_id));
             let overlaps = current_ids.iter()
@@ -1063,7 +2159,7 @@ impl RoonHandler {
                 if let Some(name) = preset.as_deref() {
                     self.matched_zones.insert(zone.zone_id.to_owned(), name.to_owned());
                     self.send_zone_list().await;
-                    self.to_app.send(IoEvent::ZonePresetMatched(preset)).await.unwrap();
+                    self.send_to_app(IoEvent::ZonePresetMatched(preset)).await;
                 }
 
                 return None;
@@ -1083,3 +2179,206 @@ impl RoonHandler {
         }
     }
 }
+
+// Returns the output id with the standby status, for outputs that support standby
+fn get_standby(output: &Output) -> Option<(String, bool)> {
+    let source_control = output.source_controls.as_ref()?
+        .iter()
+        .find(|source_control| source_control.supports_standby)?;
+
+    Some((output.output_id.to_owned(), source_control.status == "standby"))
+}
+
+// The next occurrence of the time of day, today or tomorrow
+fn get_next_alarm(time: &str) -> Option<DateTime<Local>> {
+    let time = NaiveTime::parse_from_str(time, ALARM_FORMAT).ok()?;
+    let now = Local::now();
+    let date = if now.time() < time {
+        now.date_naive()
+    } else {
+        now.date_naive().succ_opt()?
+    };
+
+    date.and_time(time).and_local_timezone(Local).earliest()
+}
+
+fn is_same_track(previous: Option<&NowPlaying>, current: Option<&NowPlaying>) -> bool {
+    match (previous, current) {
+        (Some(previous), Some(current)) => {
+            previous.three_line.line1 == current.three_line.line1
+                && previous.three_line.line2 == current.three_line.line2
+                && previous.three_line.line3 == current.three_line.line3
+                && previous.length == current.length
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+// Tracks without a length, like streams, are detected by a change of track info instead
+fn is_next_track_seek(now_playing: Option<&NowPlaying>, seek_position: Option<i64>) -> bool {
+    let has_length = now_playing
+        .and_then(|now_playing| now_playing.length)
+        .map(|length| length > 0)
+        .unwrap_or_default();
+
+    has_length && seek_position == Some(0)
+}
+
+// Sorts the output ids after the lead output, a preset matches the same set of outputs.
+// An exact lead output match is preferred, strict matching requires it
+fn get_matching_preset(
+    presets: &HashMap<String, Vec<(String, Option<f32>)>>,
+    output_ids: &mut [String],
+    strict: bool,
+) -> Option<String> {
+    if let Some(output_ids) = output_ids.get_mut(1..) {
+        output_ids.sort();
+    }
+
+    let mut matched: Option<&String> = None;
+
+    for (preset, preset_output_ids) in presets {
+        let same_outputs = output_ids.len() == preset_output_ids.len()
+            && preset_output_ids.iter().all(|(preset_output_id, _)| output_ids.contains(preset_output_id));
+
+        if !same_outputs {
+            continue;
+        }
+
+        if preset_output_ids.first().map(|(output_id, _)| output_id) == output_ids.first() {
+            return Some(preset.to_owned());
+        }
+
+        if !strict {
+            matched = match matched {
+                Some(name) if name < preset => Some(name),
+                _ => Some(preset),
+            };
+        }
+    }
+
+    matched.cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_presets(presets: &[(&str, &[&str])]) -> HashMap<String, Vec<(String, Option<f32>)>> {
+        presets.iter()
+            .map(|(name, output_ids)| {
+                let output_ids = output_ids.iter().map(|output_id| (output_id.to_string(), None)).collect();
+
+                (name.to_string(), output_ids)
+            })
+            .collect()
+    }
+
+    fn get_match(presets: &HashMap<String, Vec<(String, Option<f32>)>>, output_ids: &[&str], strict: bool) -> Option<String> {
+        let mut output_ids: Vec<String> = output_ids.iter().map(|output_id| output_id.to_string()).collect();
+
+        get_matching_preset(presets, &mut output_ids, strict)
+    }
+
+    #[test]
+    fn outputs_after_the_lead_match_in_any_order() {
+        let presets = create_presets(&[("Downstairs", &["kitchen", "dining", "living"])]);
+
+        for strict in [false, true] {
+            for output_ids in [["kitchen", "dining", "living"], ["kitchen", "living", "dining"]] {
+                assert_eq!(get_match(&presets, &output_ids, strict).as_deref(), Some("Downstairs"));
+            }
+        }
+    }
+
+    #[test]
+    fn another_lead_output_matches_loosely() {
+        let presets = create_presets(&[("Downstairs", &["kitchen", "dining", "living"])]);
+
+        for output_ids in [["dining", "kitchen", "living"], ["living", "dining", "kitchen"]] {
+            assert_eq!(get_match(&presets, &output_ids, false).as_deref(), Some("Downstairs"));
+            assert_eq!(get_match(&presets, &output_ids, true), None);
+        }
+    }
+
+    #[test]
+    fn an_exact_lead_output_is_preferred() {
+        let presets = create_presets(&[
+            ("A kitchen lead", &["kitchen", "dining"]),
+            ("B dining lead", &["dining", "kitchen"]),
+        ]);
+
+        for strict in [false, true] {
+            assert_eq!(get_match(&presets, &["dining", "kitchen"], strict).as_deref(), Some("B dining lead"));
+            assert_eq!(get_match(&presets, &["kitchen", "dining"], strict).as_deref(), Some("A kitchen lead"));
+        }
+
+        // Without a lead match the first preset by name is taken
+        let presets = create_presets(&[
+            ("B kitchen lead", &["kitchen", "dining", "living"]),
+            ("A dining lead", &["dining", "kitchen", "living"]),
+        ]);
+
+        assert_eq!(get_match(&presets, &["living", "kitchen", "dining"], false).as_deref(), Some("A dining lead"));
+    }
+
+    #[test]
+    fn other_sets_of_outputs_dont_match() {
+        let presets = create_presets(&[("Downstairs", &["kitchen", "dining", "living"])]);
+
+        for strict in [false, true] {
+            assert_eq!(get_match(&presets, &["kitchen", "dining"], strict), None);
+            assert_eq!(get_match(&presets, &["kitchen", "dining", "living", "study"], strict), None);
+            assert_eq!(get_match(&presets, &["kitchen", "dining", "study"], strict), None);
+            assert_eq!(get_match(&presets, &[], strict), None);
+        }
+    }
+
+    fn create_now_playing(title: &str, length: Option<u32>) -> NowPlaying {
+        serde_json::from_value(serde_json::json!({
+            "length": length,
+            "seek_position": 0,
+            "one_line": { "line1": title },
+            "two_line": { "line1": title, "line2": "Artist" },
+            "three_line": { "line1": title, "line2": "Artist", "line3": "Album" },
+        })).unwrap()
+    }
+
+    #[test]
+    fn track_changes_are_detected() {
+        let track = create_now_playing("Track 1", Some(300));
+        let mut seeked = track.clone();
+
+        seeked.seek_position = Some(120);
+
+        assert!(is_same_track(Some(&track), Some(&seeked)));
+        assert!(is_same_track(None, None));
+        assert!(!is_same_track(Some(&track), Some(&create_now_playing("Track 2", Some(300)))));
+        assert!(!is_same_track(Some(&track), Some(&create_now_playing("Track 1", Some(200)))));
+        assert!(!is_same_track(Some(&track), None));
+        assert!(!is_same_track(None, Some(&track)));
+
+        // Streams have no length, the track info still tells the change
+        let stream = create_now_playing("Song on air", None);
+
+        assert!(!is_same_track(Some(&stream), Some(&create_now_playing("Next song on air", None))));
+    }
+
+    #[test]
+    fn seek_to_start_is_a_track_change_for_tracks_with_a_length() {
+        let track = create_now_playing("Track 1", Some(300));
+
+        assert!(is_next_track_seek(Some(&track), Some(0)));
+        assert!(!is_next_track_seek(Some(&track), Some(1)));
+        assert!(!is_next_track_seek(Some(&track), None));
+
+        for length in [None, Some(0)] {
+            let stream = create_now_playing("Song on air", length);
+
+            assert!(!is_next_track_seek(Some(&stream), Some(0)));
+        }
+
+        assert!(!is_next_track_seek(None, Some(0)));
+    }
+}
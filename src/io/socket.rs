@@ -0,0 +1,107 @@
+use roon_api::transport::Zone;
+use serde_json::json;
+use std::{fs, io, os::unix::fs::FileTypeExt, path::{Path, PathBuf}};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::{mpsc, watch},
+    time::{timeout, Duration},
+};
+
+use crate::exec::{self, Command};
+use super::{EndPoint, IoEvent, ZoneEntry};
+
+const REPLY_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Clone, Default)]
+struct State {
+    zones: Vec<ZoneEntry>,
+    zone: Option<Zone>,
+}
+
+pub struct Socket;
+
+impl Socket {
+    // Returns the sender to be used by the Roon task, its events are passed on to the app
+    // after keeping track of the zone state needed for the replies
+    pub fn start(path: PathBuf, to_app: mpsc::Sender<IoEvent>, to_roon: mpsc::Sender<IoEvent>) -> io::Result<mpsc::Sender<IoEvent>> {
+        // Remove the socket left behind by a previous run, never another kind of file
+        if is_socket(&path) {
+            fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        let (to_socket, mut from_roon) = mpsc::channel(10);
+        let (state_tx, state_rx) = watch::channel(State::default());
+
+        tokio::spawn(async move {
+            while let Some(io_event) = from_roon.recv().await {
+                match &io_event {
+                    IoEvent::Zones(zones) => state_tx.send_modify(|state| state.zones = zones.clone()),
+                    IoEvent::ZoneChanged(zone) => state_tx.send_modify(|state| state.zone = Some(zone.clone())),
+                    IoEvent::ZoneRemoved(_) => state_tx.send_modify(|state| state.zone = None),
+                    _ => (),
+                }
+
+                if to_app.send(io_event).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_client(stream, to_roon.clone(), state_rx.clone()));
+            }
+        });
+
+        Ok(to_socket)
+    }
+}
+
+pub fn is_socket(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_socket())
+        .unwrap_or_default()
+}
+
+async fn handle_client(stream: UnixStream, to_roon: mpsc::Sender<IoEvent>, mut state: watch::Receiver<State>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let io_event = match exec::parse_command(&line) {
+            Some(Command::Event(io_event)) => Ok(*io_event),
+            Some(Command::SelectZone(name)) => {
+                match exec::find_zone_id(&state.borrow().zones, &name) {
+                    Some(zone_id) => Ok(IoEvent::ZoneSelected(EndPoint::Zone(zone_id))),
+                    None => Err(format!("Zone not found: {}", name)),
+                }
+            }
+            None => Err(format!("Unknown command: {}", line.trim())),
+        };
+
+        let reply = match io_event {
+            Ok(io_event) => {
+                state.borrow_and_update();
+
+                if to_roon.send(io_event).await.is_err() {
+                    break;
+                }
+
+                // Reply with the zone state resulting from the command, when it changes in time
+                let _ = timeout(REPLY_TIMEOUT, state.changed()).await;
+
+                match state.borrow().zone.as_ref() {
+                    Some(zone) => json!(zone),
+                    None => json!({"error": "No zone selected"}),
+                }
+            }
+            Err(err) => json!({"error": err}),
+        };
+
+        if writer.write_all(format!("{}\n", reply).as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::SecondsFormat;
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use serde::Serialize;
+use std::{io::Write, sync::Mutex};
+
+#[derive(Serialize)]
+struct Line<'a> {
+    timestamp: String,
+    level: &'a str,
+    target: &'a str,
+    message: String,
+}
+
+// Writes a JSON object per line, for supervisors that ingest structured logs
+pub struct JsonLogger<W: Write + Send> {
+    level: LevelFilter,
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send + 'static> JsonLogger<W> {
+    pub fn init(level: LevelFilter, writer: W) -> Result<(), SetLoggerError> {
+        let logger = Self {
+            level,
+            writer: Mutex::new(writer),
+        };
+
+        log::set_boxed_logger(Box::new(logger))?;
+        log::set_max_level(level);
+
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> Log for JsonLogger<W> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = Line {
+            timestamp: chrono::Local::now().to_rfc3339_opts(SecondsFormat::Micros, false),
+            level: record.level().as_str(),
+            target: record.target(),
+            message: record.args().to_string(),
+        };
+        let line = match serde_json::to_string(&line) {
+            Ok(line) => line,
+            Err(_) => return,
+        };
+
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", line);
+            let _ = writer.flush();
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.flush();
+        }
+    }
+}
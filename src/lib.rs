@@ -8,7 +8,10 @@ use ratatui::Terminal;
 use crate::app::ui;
 
 pub mod app;
+pub mod exec;
 pub mod io;
+pub mod json_log;
+pub mod rolling_log;
 
 pub async fn start_ui(app: &mut App) -> Result<()> {
     // Configure Crossterm backend for tui
@@ -21,6 +24,13 @@ pub async fn start_ui(app: &mut App) -> Result<()> {
     loop {
         terminal.draw(|rect| ui::draw(rect, app))?;
 
+        // Album art can be drawn using terminal graphics protocols, outside of the frame buffer
+        if ui::draw_graphics(terminal.backend_mut(), app)? {
+            terminal.clear()?;
+            terminal.draw(|rect| ui::draw(rect, app))?;
+            ui::draw_graphics(terminal.backend_mut(), app)?;
+        }
+
         let result = app.update_on_event().await;
 
         // Check if we should exit
@@ -34,7 +44,8 @@ pub async fn start_ui(app: &mut App) -> Result<()> {
     execute!(
         terminal.backend_mut(),
         terminal::LeaveAlternateScreen,
-        event::DisableMouseCapture
+        event::DisableMouseCapture,
+        event::DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
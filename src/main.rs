@@ -1,33 +1,87 @@
-use std::{fs, panic, path};
-use time::UtcOffset;
-use tokio::sync::mpsc;
-use eyre::Result;
-use clap::Parser;
-use roon_tui::app::App;
+use std::{env, fs, io::{self, Write}, net::IpAddr, panic, path, sync::{Arc, atomic::AtomicBool}, time::Duration};
+use time::{UtcOffset, format_description::{parse_strftime_borrowed, BorrowedFormatItem}};
+use tokio::{net::lookup_host, sync::mpsc};
+use eyre::{bail, Result};
+use clap::{Parser, ValueEnum};
+use directories::BaseDirs;
+use crossterm::terminal;
+use roon_api::RoonApi;
+use roon_tui::app::{App, AppOptions, LayoutMode, art::Protocol, history::SearchHistory, keymap::Keymap, theme::Theme};
 use roon_tui::io::{events::Events, roon::{self, Options}};
-use roon_tui::start_ui;
+#[cfg(unix)]
+use roon_tui::io::socket::{self, Socket};
+use roon_tui::{exec, json_log::JsonLogger, rolling_log::RollingLog, start_ui};
 use simplelog::{ColorChoice, ConfigBuilder, TerminalMode, TermLogger, WriteLogger, format_description};
 
+const CONFIG_FILE: &str = "config.json";
+const KEYMAP_FILE: &str = "keymap.toml";
+const THEME_FILE: &str = "theme.toml";
+const HISTORY_FILE: &str = "history.json";
 const LOG_FILE: &str = concat!(env!("CARGO_PKG_NAME"), ".log");
+const STDOUT_LOG: &str = "-";
+const LOG_TIME_FORMAT_VAR: &str = "ROON_TUI_LOG_TIME_FORMAT";
+const DISCOVERY_WINDOW: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, ValueEnum)]
+enum ThemeMode {
+    Dark,
+    Light,
+    /// Detect the terminal background using COLORFGBG
+    Auto,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum Layout {
+    /// Browse and Queue side by side
+    Horizontal,
+    /// Browse, Queue and Now Playing stacked
+    Vertical,
+    /// Stack the views on screens narrower than 80 columns
+    Auto,
+}
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
-    /// Path to the config.json file
-    #[arg(short, long, default_value = "config.json")]
-    config: String,
+    /// Path to the config.json file [default: <config dir>/roon-tui/config.json]
+    #[arg(short, long)]
+    config: Option<String>,
 
-    /// IP address of the Server, disables server discovery
+    /// IP address or host name of the Server, disables server discovery
     #[arg(short, long)]
     ip: Option<String>,
 
     /// Port number of the Server
-    #[arg(short, long, default_value = "9330")]
-    port: String,
+    #[arg(short, long, default_value_t = 9330)]
+    port: u16,
 
-    /// Path to the log file
-    #[arg(short, long, default_value = LOG_FILE)]
-    log: String,
+    /// Seconds to wait before retrying to find the Server, doubled on each failed attempt up to 30 seconds
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    discovery_interval: u64,
+
+    /// Display name of the Server to pair with during discovery, other Servers are ignored
+    #[arg(long, conflicts_with = "ip")]
+    core: Option<String>,
+
+    /// Path to the log file, - logs to stdout [default: roon-tui.log next to the config file]
+    #[arg(short, long)]
+    log: Option<String>,
+
+    /// Timestamp format of the log as strftime pattern, e.g. "%F %H:%M:%S" [env: ROON_TUI_LOG_TIME_FORMAT]
+    #[arg(long, value_name = "PATTERN")]
+    log_time_format: Option<String>,
+
+    /// Size in megabytes at which the log file is rotated
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    log_max_size: u64,
+
+    /// Number of rotated log files to keep, the previous session's log is rotated at startup
+    #[arg(long, default_value_t = 3)]
+    log_keep: usize,
+
+    /// Write the log as JSON lines with timestamp, level, target and message
+    #[arg(long)]
+    log_json: bool,
 
     /// Enable verbose logging to file
     #[arg(short, long)]
@@ -36,12 +90,172 @@ pub struct Args {
     /// Disable the use of Unicode symbols
     #[arg(short='u', long)]
     no_unicode_symbols: bool,
+
+    /// Color theme matching the terminal background
+    #[arg(short, long, value_enum, default_value_t = ThemeMode::Auto)]
+    theme: ThemeMode,
+
+    /// Disable album art in the Now Playing view
+    #[arg(long)]
+    no_art: bool,
+
+    /// Redraw interval in milliseconds while playing, for smooth progress and scrolling text
+    #[arg(long, default_value_t = 250, value_parser = clap::value_parser!(u64).range(50..))]
+    tick_rate: u64,
+
+    /// Number of volume steps per key press, the coarse volume keys use five times this value
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(i32).range(1..=100))]
+    volume_step: i32,
+
+    /// Show the volume as a bar in the Now Playing view, when the output has a volume range
+    #[arg(long)]
+    volume_bar: bool,
+
+    /// Number of queue items loaded at once, more are loaded when scrolling near the end
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..))]
+    queue_limit: u32,
+
+    /// Arrangement of the Browse and Queue views
+    #[arg(long, value_enum, default_value_t = Layout::Auto)]
+    layout: Layout,
+
+    /// Size of the Browse view in percent of the screen, the Queue view takes the rest [default: 50]
+    #[arg(long, value_parser = clap::value_parser!(u16).range(20..=80))]
+    split: Option<u16>,
+
+    /// Hide the Queue view, it can be shown again using Alt-q
+    #[arg(long)]
+    no_queue: bool,
+
+    /// Number of items moved by Page-Up and Page-Down [default: a screenful]
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    page_size: Option<u16>,
+
+    /// Move from the last list item to the first, and the other way around, when using Up and Down
+    #[arg(long)]
+    wrap: bool,
+
+    /// Show a single line per Browse and Queue item, without the subtitles, Alt-c toggles this
+    #[arg(long)]
+    compact: bool,
+
+    /// Clear the queue without asking for confirmation
+    #[arg(long)]
+    no_confirm: bool,
+
+    /// Fade out the volume during the last 30 seconds of the sleep timer, the volume is restored after pausing
+    #[arg(long)]
+    sleep_fade: bool,
+
+    /// Directory the queue is exported to from the Queue view
+    #[arg(long, default_value = ".")]
+    export_dir: String,
+
+    /// Disable all keys that control playback, the queue or the zones, e.g. for a shared display
+    #[arg(long)]
+    monitor: bool,
+
+    /// Perform a single command without starting the UI and print the resulting zone state as JSON,
+    /// e.g. play-pause, next, "volume +2" or "select-zone Kitchen"
+    #[arg(long, value_name = "COMMAND", allow_hyphen_values = true)]
+    exec: Option<String>,
+
+    /// Accept line-delimited commands, as used by --exec, on a Unix domain socket at this path
+    #[cfg(unix)]
+    #[arg(long)]
+    socket: Option<String>,
+
+    /// Print the now playing status of the selected zone as a single JSON line, without starting the UI
+    #[arg(long, conflicts_with = "exec")]
+    status: bool,
+
+    /// Like --status, but keep printing a line on each change until interrupted
+    #[arg(long, conflicts_with = "exec")]
+    watch: bool,
+
+    /// Print the Servers found by discovery within a few seconds, without starting the UI
+    #[arg(long, conflicts_with_all = ["ip", "exec", "status", "watch"])]
+    list_cores: bool,
+
+    /// Print version, configuration, terminal and Server information for bug reports, without starting the UI
+    #[arg(long, conflicts_with_all = ["exec", "status", "watch", "list_cores"])]
+    diagnostics: bool,
+}
+
+async fn validate(args: &Args) -> Result<()> {
+    if args.port == 0 {
+        bail!("Invalid port 0, use 1 to 65535");
+    }
+
+    if let Some(ip) = args.ip.as_deref() {
+        if ip.parse::<IpAddr>().is_err() {
+            if !is_host_name(ip) {
+                bail!("Invalid IP address or host name '{}'", ip);
+            }
+
+            if let Err(err) = lookup_host((ip, args.port)).await {
+                bail!("Unable to resolve host name '{}': {}", ip, err);
+            }
+        }
+    }
+
+    for (option, value) in [("--config", args.config.as_deref()), ("--log", args.log.as_deref())] {
+        if let Some(value) = value.filter(|value| path::Path::new(value).is_dir()) {
+            bail!("Invalid {} '{}', this is a directory", option, value);
+        }
+    }
+
+    // A missing export directory is created on export
+    if path::Path::new(&args.export_dir).is_file() {
+        bail!("Invalid --export-dir '{}', this is a file", args.export_dir);
+    }
+
+    // Only a socket left behind by a previous run gets replaced
+    #[cfg(unix)]
+    if let Some(socket) = args.socket.as_deref() {
+        let path = path::Path::new(socket);
+
+        if fs::symlink_metadata(path).is_ok() {
+            if !socket::is_socket(path) {
+                bail!("Invalid --socket '{}', this is not a socket", socket);
+            }
+
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                bail!("Invalid --socket '{}', this socket is in use", socket);
+            }
+        }
+    }
+
+    Ok(())
 }
 
-fn init_logger(log: String, max_log_level: log::LevelFilter) -> Result<()> {
-    let log_path = path::Path::new(&log);
-    let _ = fs::create_dir_all(log_path.parent().unwrap());
-    let time_format = format_description!("[hour]:[minute]:[second].[subsecond]");
+// Labels of letters, digits and hyphens, separated by dots,
+// a numeric last label is a malformed IP address instead
+fn is_host_name(name: &str) -> bool {
+    let is_numeric = name.rsplit('.').next().map(|label| label.chars().all(|c| c.is_ascii_digit())).unwrap_or_default();
+
+    name.len() <= 253 && !is_numeric && name.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+fn init_logger(
+    log: String,
+    max_log_level: log::LevelFilter,
+    time_pattern: Option<String>,
+    max_size: u64,
+    keep: usize,
+    json: bool,
+) -> Result<()> {
+    let custom_format = time_pattern.as_ref().map(|pattern| parse_time_pattern(pattern));
+    let time_format = match custom_format.as_ref() {
+        Some(Ok(custom_format)) => custom_format,
+        _ => format_description!("[hour]:[minute]:[second].[subsecond]"),
+    };
     let seconds = chrono::Local::now().offset().local_minus_utc();
     let utc_offset = UtcOffset::from_whole_seconds(seconds).unwrap_or(UtcOffset::UTC);
     let config = ConfigBuilder::new()
@@ -53,18 +267,39 @@ fn init_logger(log: String, max_log_level: log::LevelFilter) -> Result<()> {
         log::error!("{}", info);
     }));
 
-    match fs::File::create(log) {
-        Ok(log) => {
-            WriteLogger::init(max_log_level, config, log)?;
+    let target: io::Result<Box<dyn Write + Send>> = if log == STDOUT_LOG {
+        Ok(Box::new(io::stdout()))
+    } else {
+        let log_path = path::Path::new(&log);
+        let log_dir = match log_path.parent() {
+            Some(parent) => fs::create_dir_all(parent),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a file path", log))),
+        };
+
+        log_dir
+            .and_then(|_| RollingLog::open(log_path, max_size * 1024 * 1024, keep))
+            .map(|log| Box::new(log) as Box<dyn Write + Send>)
+    };
+
+    match target {
+        Ok(target) if json => {
+            JsonLogger::init(max_log_level, target)?;
         }
-        Err(_) => {
+        Ok(target) => {
+            WriteLogger::init(max_log_level, config, target)?;
+        }
+        Err(err) if json => {
+            JsonLogger::init(log::LevelFilter::Warn, io::stderr())?;
+            log::warn!("Logging to stderr, failed to open {}: {}", log, err);
+        }
+        Err(err) => {
             TermLogger::init(
                 log::LevelFilter::Warn,
                 config,
                 TerminalMode::Stderr,
                 ColorChoice::Never
             )?;
-            log::warn!("Logging to stderr");
+            log::warn!("Logging to stderr, failed to open {}: {}", log, err);
         }
     }
 
@@ -75,31 +310,331 @@ fn init_logger(log: String, max_log_level: log::LevelFilter) -> Result<()> {
         log::info!("Timestamps are local time");
     }
 
+    if let (Some(pattern), Some(Err(err))) = (time_pattern, custom_format) {
+        log::warn!("Invalid log time format \"{}\", using the default: {}", pattern, err);
+    }
+
     Ok(())
 }
 
+// The logger keeps the format for the lifetime of the program
+fn parse_time_pattern(pattern: &str) -> Result<&'static [BorrowedFormatItem<'static>]> {
+    let pattern: &'static str = Box::leak(pattern.to_owned().into_boxed_str());
+    let items = parse_strftime_borrowed(pattern)?;
+
+    Ok(Box::leak(items.into_boxed_slice()))
+}
+
+fn get_default_config_path() -> path::PathBuf {
+    match BaseDirs::new() {
+        Some(base_dirs) => base_dirs.config_dir().join(env!("CARGO_PKG_NAME")).join(CONFIG_FILE),
+        None => path::PathBuf::from(CONFIG_FILE),
+    }
+}
+
+fn migrate_legacy_config(config_path: &path::Path) {
+    let legacy_path = path::Path::new(CONFIG_FILE);
+
+    if config_path.exists() || !legacy_path.is_file() || config_path == legacy_path {
+        return;
+    }
+
+    if let Some(parent) = config_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    // Fall back to copy and remove when rename fails, e.g. across file systems
+    let result = fs::rename(legacy_path, config_path).or_else(|_| {
+        fs::copy(legacy_path, config_path)?;
+        fs::remove_file(legacy_path)
+    });
+
+    match result {
+        Ok(_) => log::warn!("Migrated {} to {}", legacy_path.display(), config_path.display()),
+        Err(err) => log::error!("Failed to migrate {} to {}: {}", legacy_path.display(), config_path.display(), err),
+    }
+}
+
+fn print_cores(cores: &[(String, String)]) {
+    if cores.is_empty() {
+        println!("No Roon Server found");
+        return;
+    }
+
+    let width = cores.iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max("Name".len());
+
+    println!("{:<width$}  Version", "Name");
+
+    for (name, version) in cores {
+        println!("{:<width$}  {}", name, version);
+    }
+}
+
+async fn print_diagnostics(config_path: &path::Path, ip: Option<&str>) {
+    let config = config_path.to_string_lossy();
+    let term_size = terminal::size()
+        .map(|(width, height)| format!("{}x{}", width, height))
+        .unwrap_or_else(|_| "unknown".to_owned());
+    let get_env = |name| env::var(name).unwrap_or_else(|_| "-".to_owned());
+
+    println!("roon-tui:      {}", env!("CARGO_PKG_VERSION"));
+    println!("roon-api:      {}", env!("ROON_API_REVISION"));
+    println!("OS:            {} ({})", env::consts::OS, env::consts::ARCH);
+    println!("TERM:          {}", get_env("TERM"));
+    println!("TERM_PROGRAM:  {}", get_env("TERM_PROGRAM"));
+    println!("COLORTERM:     {}", get_env("COLORTERM"));
+    println!("Terminal size: {}", term_size);
+    println!("Album art:     {:?}", Protocol::detect());
+    println!("Config file:   {} ({})", config, if config_path.exists() {"exists"} else {"missing"});
+
+    let paired = RoonApi::load_config(&config, "roonstate").get("paired_core_id").is_some();
+
+    println!("Paired:        {}", if paired {"yes"} else {"no"});
+
+    match ip {
+        Some(ip) => println!("Discovery:     skipped, using --ip {}", ip),
+        None => {
+            let cores = roon::discover_cores(&config, DISCOVERY_WINDOW).await;
+
+            if cores.is_empty() {
+                println!("Discovery:     no Roon Server found");
+            }
+
+            for (name, version) in cores {
+                println!("Discovery:     {} ({})", name, version);
+            }
+        }
+    }
+
+    let mut settings = RoonApi::load_config(&config, "settings");
+
+    redact_secrets(&mut settings);
+
+    println!("Settings:");
+    println!("{}", serde_json::to_string_pretty(&settings).unwrap_or_default());
+}
+
+// Values of keys that look like credentials are left out of pasted reports
+fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+
+                if key.contains("token") || key.contains("secret") || key.contains("password") {
+                    *value = serde_json::Value::from("<redacted>");
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_secrets),
+        _ => (),
+    }
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
 async fn main() -> Result<()> {
     let (to_app, from_roon) = mpsc::channel(10);
     let (to_roon, from_app) = mpsc::channel(10);
     let args = Args::parse();
-    let mut app = App::new(to_roon, from_roon, args.no_unicode_symbols);
-    let options = Options {
-        config: args.config,
-        ip: args.ip,
-        port: args.port,
-    };
+
+    // Fail before the terminal is taken over by the UI
+    validate(&args).await?;
+
     let max_log_level = if args.verbose {
         log::LevelFilter::Info
     } else {
         log::LevelFilter::Warn
     };
 
-    let _ = init_logger(args.log, max_log_level);
+    let config_path = match args.config.as_deref() {
+        Some(config) => path::PathBuf::from(config),
+        None => get_default_config_path(),
+    };
+    let log = match args.log {
+        Some(log) => log,
+        None => config_path.with_file_name(LOG_FILE).to_string_lossy().into_owned(),
+    };
+
+    let log_time_format = args.log_time_format.or_else(|| env::var(LOG_TIME_FORMAT_VAR).ok());
 
-    Events::start(to_app.clone());
+    let _ = init_logger(log, max_log_level, log_time_format, args.log_max_size, args.log_keep, args.log_json);
+
+    if args.config.is_none() {
+        migrate_legacy_config(&config_path);
+    }
+
+    let overview_open = Arc::new(AtomicBool::new(false));
+    let options = Options {
+        config: config_path.to_string_lossy().into_owned(),
+        ip: args.ip,
+        port: args.port,
+        discovery_interval: Duration::from_secs(args.discovery_interval),
+        core: args.core,
+        // Album art is only used by the UI
+        no_art: args.no_art || args.exec.is_some() || args.status || args.watch,
+        queue_limit: args.queue_limit,
+        sleep_fade: args.sleep_fade,
+        overview_open: overview_open.clone(),
+    };
+
+    if args.diagnostics {
+        print_diagnostics(&config_path, options.ip.as_deref()).await;
+
+        return Ok(());
+    }
+
+    if args.list_cores {
+        let cores = roon::discover_cores(&options.config, DISCOVERY_WINDOW).await;
+
+        print_cores(&cores);
+
+        return Ok(());
+    }
+
+    if let Some(command) = args.exec {
+        roon::start(options, to_app, from_app).await;
+
+        return exec::run(&command, to_roon, from_roon).await;
+    }
+
+    if args.status || args.watch {
+        roon::start(options, to_app, from_app).await;
+
+        return exec::status(from_roon, args.watch).await;
+    }
+
+    let keymap = Keymap::load(&config_path.with_file_name(KEYMAP_FILE));
+    let theme = match args.theme {
+        ThemeMode::Dark => Theme::dark(),
+        ThemeMode::Light => Theme::light(),
+        ThemeMode::Auto => Theme::auto(),
+    };
+    let theme = Theme::load(&config_path.with_file_name(THEME_FILE), theme);
+    let search_history = SearchHistory::load(&config_path.with_file_name(HISTORY_FILE));
+    let ticking = Arc::new(AtomicBool::new(false));
+    // Commands on the socket are passed to the Roon task, next to the ones of the app
+    #[cfg(unix)]
+    let socket = args.socket.map(path::PathBuf::from);
+    #[cfg(unix)]
+    let to_app = match socket.as_ref() {
+        Some(socket) => match Socket::start(socket.clone(), to_app, to_roon.clone()) {
+            Ok(to_app) => to_app,
+            Err(err) => bail!("Failed to bind socket '{}': {}", socket.display(), err),
+        },
+        None => to_app,
+    };
+    let layout_mode = match args.layout {
+        Layout::Horizontal => LayoutMode::Horizontal,
+        Layout::Vertical => LayoutMode::Vertical,
+        Layout::Auto => LayoutMode::Auto,
+    };
+    let app_options = AppOptions {
+        no_unicode_symbols: args.no_unicode_symbols,
+        keymap,
+        theme,
+        search_history,
+        no_art: args.no_art,
+        volume_step: args.volume_step,
+        volume_bar: args.volume_bar,
+        no_confirm: args.no_confirm,
+        monitor: args.monitor,
+        export_dir: path::PathBuf::from(args.export_dir),
+        split: args.split,
+        layout_mode,
+        no_queue: args.no_queue,
+        page_size: args.page_size.map(usize::from),
+        wrap: args.wrap,
+        compact: args.compact,
+    };
+    let mut app = App::new(to_roon, from_roon, ticking.clone(), overview_open, app_options);
+
+    Events::start(to_app.clone(), Duration::from_millis(args.tick_rate), ticking);
 
     roon::start(options, to_app, from_app).await;
 
-    start_ui(&mut app).await
+    let result = start_ui(&mut app).await;
+
+    // The socket is only there when it was bound, a failed bind ended the program
+    #[cfg(unix)]
+    if let Some(socket) = socket {
+        let _ = fs::remove_file(socket);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse_from([env!("CARGO_PKG_NAME")].iter().chain(args))
+    }
+
+    #[tokio::test]
+    async fn valid_arguments() {
+        let temp_dir = env::temp_dir();
+        let temp_dir = temp_dir.to_string_lossy();
+
+        assert!(validate(&parse(&[])).await.is_ok());
+        assert!(validate(&parse(&["--ip", "192.168.1.10", "--port", "9100"])).await.is_ok());
+        assert!(validate(&parse(&["--ip", "::1"])).await.is_ok());
+        assert!(validate(&parse(&["--ip", "localhost"])).await.is_ok());
+        assert!(validate(&parse(&["--log", "-", "--export-dir", &temp_dir])).await.is_ok());
+        assert!(validate(&parse(&["--export-dir", "does/not/exist/yet"])).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn invalid_arguments() {
+        let temp_dir = env::temp_dir();
+        let temp_dir = temp_dir.to_string_lossy();
+        let file = env::temp_dir().join(format!("{}-validate-test", env!("CARGO_PKG_NAME")));
+
+        fs::write(&file, "").unwrap();
+
+        let file_name = file.to_string_lossy();
+
+        assert!(validate(&parse(&["--port", "0"])).await.is_err());
+        assert!(validate(&parse(&["--ip", "192.168.1.300"])).await.is_err());
+        assert!(validate(&parse(&["--ip", "roon server"])).await.is_err());
+        assert!(validate(&parse(&["--ip=-core.local"])).await.is_err());
+        assert!(validate(&parse(&["--ip", "core..local"])).await.is_err());
+        assert!(validate(&parse(&["--config", &temp_dir])).await.is_err());
+        assert!(validate(&parse(&["--log", &temp_dir])).await.is_err());
+        assert!(validate(&parse(&["--export-dir", &file_name])).await.is_err());
+        #[cfg(unix)]
+        assert!(validate(&parse(&["--socket", &file_name])).await.is_err());
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn only_a_stale_socket_is_replaced() {
+        let socket = env::temp_dir().join(format!("{}-socket-test", env!("CARGO_PKG_NAME")));
+        let _ = fs::remove_file(&socket);
+        let socket_name = socket.to_string_lossy();
+        let listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        assert!(validate(&parse(&["--socket", &socket_name])).await.is_err());
+
+        drop(listener);
+
+        assert!(validate(&parse(&["--socket", &socket_name])).await.is_ok());
+
+        let _ = fs::remove_file(socket);
+    }
+
+    #[test]
+    fn non_numeric_port_is_rejected() {
+        let result = Args::try_parse_from([env!("CARGO_PKG_NAME"), "--port", "93x0"]);
+
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,72 @@
+use std::{fs::{self, File}, io::{self, Write}, path::{Path, PathBuf}};
+
+// A log file that is moved aside when it grows too large, or when a new session starts,
+// the backups are numbered from the most recent: roon-tui.log.1, roon-tui.log.2, ...
+pub struct RollingLog {
+    path: PathBuf,
+    max_size: u64,
+    keep: usize,
+    file: File,
+    size: u64,
+}
+
+impl RollingLog {
+    pub fn open(path: &Path, max_size: u64, keep: usize) -> io::Result<Self> {
+        rotate(path, keep)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            max_size,
+            keep,
+            file: File::create(path)?,
+            size: 0,
+        })
+    }
+}
+
+impl Write for RollingLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.file.flush()?;
+            rotate(&self.path, self.keep)?;
+            self.file = File::create(&self.path)?;
+            self.size = 0;
+        }
+
+        let len = self.file.write(buf)?;
+
+        self.size += len as u64;
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn rotate(path: &Path, keep: usize) -> io::Result<()> {
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    let _ = fs::remove_file(get_backup_path(path, keep));
+
+    for index in (1..keep).rev() {
+        let backup = get_backup_path(path, index);
+
+        if backup.exists() {
+            fs::rename(backup, get_backup_path(path, index + 1))?;
+        }
+    }
+
+    fs::rename(path, get_backup_path(path, 1))
+}
+
+fn get_backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+
+    backup.push(format!(".{}", index));
+
+    PathBuf::from(backup)
+}